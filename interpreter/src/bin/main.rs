@@ -1,63 +1,191 @@
-use clap::Parser;
-use std::{fs::File, io::prelude::*, process::ExitCode};
+use clap::{Parser, Subcommand};
+use std::{
+    fs::File,
+    io::{prelude::*, stdout, BufWriter},
+    process::ExitCode,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use interpreter::*;
 
 #[derive(Parser)]
-#[command(version)]
+#[command(version, args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// FactoryScript program to execute
     file: Option<String>,
 
+    /// Arguments passed to the program
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    args: Vec<String>,
+
     /// Print benchmarking information after completion
     #[arg(short, long)]
     benchmark: bool,
 
+    /// Print how often each station triggered and each function was invoked
+    /// after completion, with a heatmap of the source
+    #[arg(short, long)]
+    profile: bool,
+
+    /// Print the source with the stations that never triggered marked after
+    /// completion, and a summary of the stations and belts that were never used
+    #[arg(short, long)]
+    coverage: bool,
+
+    /// Write the coverage of stations and belts to a file in the lcov format
+    #[arg(long, value_name = "FILE")]
+    lcov: Option<String>,
+
+    /// Write a JSON Lines record of every execution step to a file
+    #[arg(long, value_name = "FILE")]
+    trace: Option<String>,
+
+    /// Abort execution after this many steps
+    #[arg(long, value_name = "STEPS")]
+    max_steps: Option<u64>,
+
+    /// Abort execution after this many seconds
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    timeout: Option<Duration>,
+
+    /// Maximum depth of nested function invocations [default: unlimited]
+    #[arg(long, value_name = "DEPTH")]
+    max_depth: Option<u32>,
+
+    /// Print the runtime state if execution stops with an error or is interrupted
+    /// with Ctrl-C
+    #[arg(long)]
+    dump_state: bool,
+
+    /// Report pallets left in stations and function outputs never produced when
+    /// the program ends: off, warn or error
+    #[arg(long, value_name = "MODE", default_value = "off", value_parser = parse_stall_check)]
+    stalls: StallCheck,
+
+    /// What integer arithmetic does when its result is out of range: error, wrap
+    /// or saturate
+    #[arg(long, value_name = "MODE", default_value = "error", value_parser = parse_overflow)]
+    overflow: Overflow,
+
     /// Increase debug logging level, can be supplied multiple times
-    #[arg(short = 'd', long = "debug", action = clap::ArgAction::Count)]
+    #[arg(short = 'd', long = "debug", action = clap::ArgAction::Count, global = true)]
     debug_level: u8,
 
     /// Disable colored terminal output
-    #[arg(long = "no-color")]
+    #[arg(long = "no-color", global = true)]
     no_color: bool,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Step through a FactoryScript program in the interactive debugger
+    Debug {
+        /// FactoryScript program to debug
+        file: String,
+
+        /// Arguments passed to the program
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+/// Parses how to report unfinished work at the end of a program
+fn parse_stall_check(s: &str) -> Result<StallCheck, String> {
+    return match s {
+        "off" => Ok(StallCheck::Off),
+        "warn" => Ok(StallCheck::Warn),
+        "error" => Ok(StallCheck::Error),
+        _ => Err(String::from("expected 'off', 'warn' or 'error'")),
+    };
+}
+
+/// Parses what to do when integer arithmetic overflows
+fn parse_overflow(s: &str) -> Result<Overflow, String> {
+    return match s {
+        "error" => Ok(Overflow::Error),
+        "wrap" => Ok(Overflow::Wrapping),
+        "saturate" => Ok(Overflow::Saturating),
+        _ => Err(String::from("expected 'error', 'wrap' or 'saturate'")),
+    };
+}
+
+/// Parses a non-negative number of seconds into a duration
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    let secs: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    return Duration::try_from_secs_f64(secs).map_err(|e| format!("{e}"));
+}
+
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
-    // setting global options
-    unsafe {
-        DEBUG_LEVEL = cli.debug_level;
-        COLOR_OUTPUT = !cli.no_color;
-        debug!(1, "Debug level:\t{}", DEBUG_LEVEL);
-    }
+    let mut options = RunOptions {
+        debug_level: cli.debug_level,
+        color: !cli.no_color,
+        benchmark: cli.benchmark,
+        max_steps: cli.max_steps,
+        timeout: cli.timeout,
+        max_depth: cli.max_depth,
+        dump_state: cli.dump_state,
+        stall_check: cli.stalls,
+        overflow: cli.overflow,
+        profile: cli.profile,
+        coverage: cli.coverage || cli.lcov.is_some(),
+        ..Default::default()
+    };
+    debug!(options, 1, "Debug level:\t{}", cli.debug_level);
 
     // reading file
-    let file_name: String = match cli.file {
-        Some(s) => s,
-        None => {
-            print_cli_err!("No file provided");
+    let debugging = matches!(cli.command, Some(Command::Debug { .. }));
+    let file_name: String = match (cli.command, cli.file) {
+        (Some(Command::Debug { file, args }), _) => {
+            options.args = args;
+            file
+        }
+        (None, Some(file)) => {
+            options.args = cli.args;
+            file
+        }
+        (None, None) => {
+            print_cli_err!(options.color, "No file provided");
             return ExitCode::FAILURE;
         }
     };
     let mut file = match File::open(&file_name) {
         Ok(f) => f,
         Err(e) => {
-            print_cli_err!("Failed to open file \"{}\": {}", file_name, e);
+            print_cli_err!(
+                options.color,
+                "Failed to open file \"{}\": {}",
+                file_name,
+                e
+            );
             return ExitCode::FAILURE;
         }
     };
-    debug!(2, "Opened file");
+    debug!(options, 2, "Opened file");
     let mut file_contents = String::new();
     let bytes_read = match file.read_to_string(&mut file_contents) {
         Ok(b) => b,
         Err(e) => {
-            print_cli_err!("Failed to read file \"{}\": {}", file_name, e);
+            print_cli_err!(
+                options.color,
+                "Failed to read file \"{}\": {}",
+                file_name,
+                e
+            );
             return ExitCode::FAILURE;
         }
     };
-    debug!(2, "Read {} bytes", bytes_read);
+    debug!(options, 2, "Read {} bytes", bytes_read);
     debug!(
+        options,
         1,
         "Input file:\t{} ({})",
         file_name,
@@ -68,10 +196,115 @@ fn main() -> ExitCode {
         }
     );
 
-    match run(&file_contents, cli.benchmark) {
-        Ok(()) => ExitCode::SUCCESS,
+    if let Some(trace_name) = cli.trace {
+        match File::create(&trace_name) {
+            Ok(f) => options.trace = Some(Box::new(BufWriter::new(f))),
+            Err(e) => {
+                print_cli_err!(
+                    options.color,
+                    "Failed to create trace file \"{}\": {}",
+                    trace_name,
+                    e
+                );
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let mut lcov = None;
+    if let Some(lcov_name) = &cli.lcov {
+        match File::create(lcov_name) {
+            Ok(f) => lcov = Some(BufWriter::new(f)),
+            Err(e) => {
+                print_cli_err!(
+                    options.color,
+                    "Failed to create lcov file \"{}\": {}",
+                    lcov_name,
+                    e
+                );
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let program = match load(&file_contents, &options) {
+        Ok(p) => p,
+        Err(errors) => {
+            for e in errors.iter() {
+                print_cli_err!(options.color, "{}", e.pretty_msg(&file_contents));
+            }
+            return ExitCode::FAILURE;
+        }
+    };
+    for w in program.warnings() {
+        print_cli_warn!(options.color, "{}", w.pretty_msg(&file_contents));
+    }
+
+    if options.dump_state && !debugging {
+        // the first Ctrl-C stops execution after the current step, a second one
+        // exits immediately
+        let interrupt = Arc::new(AtomicBool::new(false));
+        let flag = interrupt.clone();
+        let res = ctrlc::set_handler(move || {
+            if flag.swap(true, Ordering::Relaxed) {
+                std::process::exit(130);
+            }
+        });
+        if let Err(e) = res {
+            print_cli_err!(options.color, "Failed to set Ctrl-C handler: {}", e);
+            return ExitCode::FAILURE;
+        }
+        options.interrupt = Some(interrupt);
+    }
+
+    // coverage is written for runs that failed too, up to where they stopped
+    let color = options.color;
+    let mut write_coverage = |coverage: &Coverage| {
+        if cli.coverage {
+            let mut out = stdout();
+            let _ = writeln!(out);
+            let _ = coverage.write_listing(&file_contents, color, &mut out);
+            let _ = coverage.write_summary(&mut out);
+        }
+        if let Some(out) = lcov.as_mut() {
+            let res = coverage
+                .write_lcov(&file_name, out)
+                .and_then(|_| out.flush());
+            if let Err(e) = res {
+                print_cli_err!(color, "Failed to write lcov file: {}", e);
+            }
+        }
+    };
+
+    let res = if debugging {
+        debugger::run_debugger(&program, &mut options)
+    } else {
+        program.run(&mut options).map(|report| {
+            for w in report.warnings.iter() {
+                print_cli_warn!(color, "{}", w.pretty_msg(&file_contents));
+            }
+            if let Some(profile) = &report.profile {
+                let mut out = stdout();
+                let _ = profile.write_table(&mut out);
+                let _ = writeln!(out);
+                let _ = profile.write_heatmap(&file_contents, color, &mut out);
+            }
+            if let Some(coverage) = &report.coverage {
+                write_coverage(coverage);
+            }
+            report.exit_status
+        })
+    };
+    match res {
+        Ok(status) => ExitCode::from(status),
         Err(e) => {
-            print_cli_err!("{}", e.pretty_msg(&file_contents));
+            print_cli_err!(color, "{}", e.pretty_msg(&file_contents));
+            if let Some(state) = &e.state {
+                println!("{state}");
+            }
+            if let Some(coverage) = &e.coverage {
+                write_coverage(coverage);
+            }
             ExitCode::FAILURE
         }
     }
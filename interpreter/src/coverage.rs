@@ -0,0 +1,297 @@
+use std::io::{self, Write};
+
+use crate::{
+    profile::{write_colored_line, Profiler},
+    runtime::StepLog,
+    station::types,
+    util::SourceSpan,
+    FSProgram,
+};
+
+/// How often a station triggered during an execution
+#[derive(Debug, Clone)]
+pub struct StationCoverage {
+    /// Identifier of the station's type
+    pub station: &'static str,
+    /// Location of the station in source code
+    pub loc: SourceSpan,
+    /// Name of the function the station is in
+    pub function: String,
+    /// Number of times the station triggered, in every invocation of its function.
+    /// Start and function input stations "trigger" when their function is invoked
+    pub fires: u64,
+}
+
+/// How many pallets a conveyor belt carried during an execution
+#[derive(Debug, Clone)]
+pub struct BeltCoverage {
+    /// Identifier of the type of the station the belt starts at
+    pub from_station: &'static str,
+    /// Location of the station the belt starts at
+    pub from: SourceSpan,
+    /// Identifier of the type of the station the belt leads to
+    pub to_station: &'static str,
+    /// Location of the station the belt leads to
+    pub to: SourceSpan,
+    /// Priority of the in bay the belt leads to
+    pub bay: u32,
+    /// Name of the function the belt is in
+    pub function: String,
+    /// Number of pallets the belt carried, in every invocation of its function
+    pub pallets: u64,
+}
+
+/// How often a function was invoked during an execution
+#[derive(Debug, Clone)]
+pub struct FunctionCoverage {
+    pub name: String,
+    /// Location of the function's start station, or of one of its input stations
+    pub loc: SourceSpan,
+    pub invocations: u64,
+}
+
+/// Which stations triggered and which belts carried pallets during an execution
+#[derive(Debug, Clone)]
+pub struct Coverage {
+    /// Every station that is reachable, in source order for each function
+    pub stations: Vec<StationCoverage>,
+    /// Every belt between reachable stations, in order of the station they start at
+    pub belts: Vec<BeltCoverage>,
+    /// Every function, main first
+    pub functions: Vec<FunctionCoverage>,
+}
+impl Coverage {
+    /// Writes a copy of the source with the stations that never triggered marked
+    /// under each line, in red if colored. Stations that triggered are green
+    pub fn write_listing(&self, src: &str, color: bool, out: &mut dyn Write) -> io::Result<()> {
+        for (line_i, line) in src.split('\n').enumerate() {
+            let mut stations: Vec<&StationCoverage> = self
+                .stations
+                .iter()
+                .filter(|s| s.loc.pos.line == line_i)
+                .collect();
+            stations.sort_by_key(|s| s.loc.pos.col);
+
+            write!(out, "{:>4} | ", line_i + 1)?;
+            if color {
+                let spans = stations
+                    .iter()
+                    .map(|s| (s.loc, if s.fires > 0 { "\x1b[32m" } else { "\x1b[31m" }))
+                    .collect();
+                write_colored_line(out, line, spans)?;
+            } else {
+                write!(out, "{line}")?;
+            }
+            writeln!(out)?;
+
+            let mut marks = String::new();
+            for s in stations.iter().filter(|s| s.fires == 0) {
+                marks.push_str(&" ".repeat(s.loc.pos.col - marks.chars().count()));
+                marks.push_str(&"^".repeat(s.loc.len));
+            }
+            if !marks.is_empty() {
+                if color {
+                    writeln!(out, "     | \x1b[31m{marks} never triggered\x1b[0m")?;
+                } else {
+                    writeln!(out, "     | {marks} never triggered")?;
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    /// Writes the share of stations that triggered and belts that carried pallets,
+    /// and lists the ones that didn't
+    pub fn write_summary(&self, out: &mut dyn Write) -> io::Result<()> {
+        let fired = self.stations.iter().filter(|s| s.fires > 0).count();
+        let carried = self.belts.iter().filter(|b| b.pallets > 0).count();
+
+        writeln!(out, "\n======Coverage=======")?;
+        writeln!(
+            out,
+            " stations {fired}/{} triggered ({})",
+            self.stations.len(),
+            percent(fired, self.stations.len())
+        )?;
+        writeln!(
+            out,
+            " belts    {carried}/{} carried pallets ({})",
+            self.belts.len(),
+            percent(carried, self.belts.len())
+        )?;
+        if fired < self.stations.len() {
+            writeln!(out, "\n never triggered:")?;
+            for s in self.stations.iter().filter(|s| s.fires == 0) {
+                writeln!(out, "   {} @ {} in '{}'", s.station, s.loc, s.function)?;
+            }
+        }
+        if carried < self.belts.len() {
+            writeln!(out, "\n never carried pallets:")?;
+            for b in self.belts.iter().filter(|b| b.pallets == 0) {
+                writeln!(
+                    out,
+                    "   {} @ {} to {} @ {} bay {} in '{}'",
+                    b.from_station, b.from, b.to_station, b.to, b.bay, b.function
+                )?;
+            }
+        }
+        writeln!(out, "=====================")?;
+        return Ok(());
+    }
+
+    /// Writes the coverage in the lcov tracefile format, for the source file at
+    /// `path`. Lines are covered if every station on them triggered. Each belt is
+    /// a branch of the line its station starts on, numbered by the station's column
+    pub fn write_lcov(&self, path: &str, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "TN:")?;
+        writeln!(out, "SF:{path}")?;
+        for f in self.functions.iter() {
+            writeln!(out, "FN:{},{}", f.loc.pos.line + 1, f.name)?;
+        }
+        for f in self.functions.iter() {
+            writeln!(out, "FNDA:{},{}", f.invocations, f.name)?;
+        }
+        writeln!(out, "FNF:{}", self.functions.len())?;
+        writeln!(
+            out,
+            "FNH:{}",
+            self.functions.iter().filter(|f| f.invocations > 0).count()
+        )?;
+
+        for (i, b) in self.belts.iter().enumerate() {
+            let taken = if b.pallets > 0 {
+                b.pallets.to_string()
+            } else {
+                String::from("-")
+            };
+            writeln!(
+                out,
+                "BRDA:{},{},{i},{taken}",
+                b.from.pos.line + 1,
+                b.from.pos.col
+            )?;
+        }
+        writeln!(out, "BRF:{}", self.belts.len())?;
+        writeln!(
+            out,
+            "BRH:{}",
+            self.belts.iter().filter(|b| b.pallets > 0).count()
+        )?;
+
+        // a line's count is the lowest count of its stations
+        let mut lines: Vec<(usize, u64)> = Vec::new();
+        for s in self.stations.iter() {
+            let line = s.loc.pos.line + 1;
+            match lines.iter_mut().find(|(l, _)| *l == line) {
+                Some((_, count)) => *count = (*count).min(s.fires),
+                None => lines.push((line, s.fires)),
+            }
+        }
+        lines.sort_unstable();
+        for (line, count) in lines.iter() {
+            writeln!(out, "DA:{line},{count}")?;
+        }
+        writeln!(out, "LF:{}", lines.len())?;
+        writeln!(out, "LH:{}", lines.iter().filter(|(_, c)| *c > 0).count())?;
+        writeln!(out, "end_of_record")?;
+        return Ok(());
+    }
+}
+
+/// Formats a share as a percentage, 100% if there is nothing to cover
+fn percent(n: usize, total: usize) -> String {
+    if total == 0 {
+        return String::from("100.0%");
+    }
+    return format!("{:.1}%", n as f64 * 100.0 / total as f64);
+}
+
+/// Builds a coverage report from the log of every step, on top of the trigger and
+/// invocation counts of a profile
+pub(crate) struct CoverageRecorder<'a> {
+    program: &'a FSProgram,
+    profiler: Profiler<'a>,
+    /// Number of times every station put pallets on its belts, indexed by
+    /// function and station index
+    emits: Vec<Vec<u64>>,
+}
+impl<'a> CoverageRecorder<'a> {
+    pub fn new(program: &'a FSProgram) -> Self {
+        let mut emits: Vec<Vec<u64>> = program
+            .function_templates
+            .iter()
+            .map(|f| vec![0; f.stations.len()])
+            .collect();
+        // main is invoked once, its start stations put pallets on their belts
+        for (i, s) in program.main.stations.iter().enumerate() {
+            if s.s_type == &types::MAIN {
+                emits[0][i] = 1;
+            }
+        }
+        return CoverageRecorder {
+            program,
+            profiler: Profiler::new(program),
+            emits,
+        };
+    }
+
+    /// Counts the stations triggered and belts used during a step
+    pub fn record(&mut self, log: &StepLog) {
+        self.profiler.record(log);
+        for emit in log.emitted.iter() {
+            let function = self.program.template_index(emit.function);
+            self.emits[function][emit.station] += 1;
+        }
+    }
+
+    pub fn finish(self) -> Coverage {
+        let profile = self.profiler.finish();
+        let mut profiled = profile.stations.iter();
+        let mut stations: Vec<StationCoverage> = Vec::new();
+        let mut belts: Vec<BeltCoverage> = Vec::new();
+        for (f, emits) in self.program.function_templates.iter().zip(self.emits) {
+            for (i, s) in f.stations.iter().enumerate() {
+                let fires = profiled.next().map_or(0, |p| p.fires);
+                let start = s.s_type == &types::MAIN || s.s_type == &types::FUNC_INPUT;
+                stations.push(StationCoverage {
+                    station: s.s_type.id,
+                    loc: s.loc,
+                    function: f.name.clone(),
+                    fires: if start { emits[i] } else { fires },
+                });
+                for (dest, bay) in s.out_bays.iter() {
+                    let to = &f.stations[*dest];
+                    belts.push(BeltCoverage {
+                        from_station: s.s_type.id,
+                        from: s.loc,
+                        to_station: to.s_type.id,
+                        to: to.loc,
+                        bay: *bay,
+                        function: f.name.clone(),
+                        pallets: emits[i],
+                    });
+                }
+            }
+        }
+        let functions = self
+            .program
+            .function_templates
+            .iter()
+            .zip(profile.functions)
+            .map(|(f, profiled)| FunctionCoverage {
+                name: f.name.clone(),
+                loc: f
+                    .stations
+                    .iter()
+                    .find(|s| s.s_type == &types::MAIN || s.s_type == &types::FUNC_INPUT)
+                    .map_or(SourceSpan::zero(), |s| s.loc),
+                invocations: profiled.invocations,
+            })
+            .collect();
+        return Coverage {
+            stations,
+            belts,
+            functions,
+        };
+    }
+}
@@ -0,0 +1,320 @@
+use std::io::{stdout, Write};
+
+use crate::{
+    runtime::*,
+    station::{types::Context, Station, StationData},
+    util::*,
+    *,
+};
+
+/// Place the debugger should pause execution at
+enum Breakpoint {
+    /// Pause when the station at this position is triggered
+    Station(SourcePos),
+    /// Pause when a function with this name is invoked
+    Function(String),
+}
+impl std::fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Breakpoint::Station(pos) => write!(f, "station @ {pos}"),
+            Breakpoint::Function(name) => write!(f, "function '{name}'"),
+        }
+    }
+}
+
+const HELP_MSG: &str = "Commands:
+  s, step [n]          Perform n steps (default 1)
+  c, continue          Run until a breakpoint is hit or the program ends
+  b, break <loc|name>  Set a breakpoint on the station at a location (line:col)
+                       or on invocations of a function
+  d, delete <n>        Delete breakpoint number n
+  l, breakpoints       List breakpoints
+  p, pallets           Show pallets that are moving on conveyor belts
+  i, inspect [loc]     Show the in bays of all occupied stations, or of the
+                       station at a location (line:col)
+  f, functions         Show the tree of live functions
+  h, help              Show this message
+  q, quit              Stop debugging
+An empty line repeats the previous command";
+
+/// Runs a program in an interactive step debugger, reading commands from the
+/// input in `options`. The program shares that input with the debugger. Returns
+/// the program's exit status, or 0 if debugging was stopped early
+pub fn run_debugger(program: &FSProgram, options: &mut RunOptions) -> Result<u8, Error> {
+    let mut runtime = Runtime::new(program);
+    runtime.max_depth = options.max_depth;
+    let mut breakpoints: Vec<Breakpoint> = Vec::new();
+
+    println!("FactoryScript debugger, type 'help' for a list of commands");
+    let mut last_cmd = String::new();
+    loop {
+        if runtime.is_done() {
+            if let Some(status) = runtime.exit_status {
+                println!(
+                    "Program exited with status {status} after {} steps",
+                    runtime.step_count
+                );
+                return Ok(status);
+            }
+            println!("Program finished after {} steps", runtime.step_count);
+            return Ok(0);
+        }
+
+        let _ = options.output.flush();
+        print!("(step {}) ", runtime.step_count);
+        let _ = stdout().flush();
+        let mut line = String::new();
+        match options.input.read_line(&mut line) {
+            Ok(0) => return Ok(0),
+            Ok(_) => {}
+            Err(e) => {
+                return Err(Error::new(
+                    RuntimeError,
+                    SourceSpan::zero(),
+                    format!("Failed to read debugger command: {e}"),
+                ));
+            }
+        }
+        let mut line = line.trim().to_owned();
+        if line.is_empty() {
+            line = last_cmd.clone();
+        } else {
+            last_cmd = line.clone();
+        }
+        let mut words = line.split_whitespace();
+        let cmd = words.next().unwrap_or("");
+        let arg = words.next();
+
+        match cmd {
+            "" => {}
+            "s" | "step" => {
+                let n = match arg.map(|a| a.parse::<u64>()) {
+                    None => 1,
+                    Some(Ok(n)) => n,
+                    Some(Err(_)) => {
+                        println!("Invalid step count \"{}\"", arg.unwrap());
+                        continue;
+                    }
+                };
+                for _ in 0..n {
+                    if runtime.is_done() {
+                        break;
+                    }
+                    runtime.step(&mut Context {
+                        input: &mut options.input,
+                        output: &mut options.output,
+                        args: &options.args,
+                        overflow: options.overflow,
+                    })?;
+                    print_fired(&runtime);
+                    if hit_breakpoint(program, &runtime, &breakpoints) {
+                        break;
+                    }
+                }
+            }
+            "c" | "continue" => {
+                while !runtime.is_done() {
+                    runtime.step(&mut Context {
+                        input: &mut options.input,
+                        output: &mut options.output,
+                        args: &options.args,
+                        overflow: options.overflow,
+                    })?;
+                    if hit_breakpoint(program, &runtime, &breakpoints) {
+                        print_fired(&runtime);
+                        break;
+                    }
+                }
+            }
+            "b" | "break" => {
+                let arg = match arg {
+                    Some(a) => a,
+                    None => {
+                        println!("Expected a location (line:col) or a function name");
+                        continue;
+                    }
+                };
+                let bp = if let Some(pos) = parse_pos(arg) {
+                    if find_station(program, pos).is_none() {
+                        println!("No station found at {pos}");
+                        continue;
+                    }
+                    Breakpoint::Station(pos)
+                } else {
+                    let name = arg.trim_start_matches('$');
+                    if !program.function_templates.iter().any(|f| f.name == name) {
+                        println!("No function named '{name}'");
+                        continue;
+                    }
+                    Breakpoint::Function(name.to_owned())
+                };
+                println!("Breakpoint {} set on {bp}", breakpoints.len());
+                breakpoints.push(bp);
+            }
+            "d" | "delete" => match arg.and_then(|a| a.parse::<usize>().ok()) {
+                Some(n) if n < breakpoints.len() => {
+                    println!("Deleted breakpoint {n} on {}", breakpoints.remove(n));
+                }
+                _ => println!("Expected a valid breakpoint number"),
+            },
+            "l" | "breakpoints" => {
+                if breakpoints.is_empty() {
+                    println!("No breakpoints set");
+                }
+                for (i, bp) in breakpoints.iter().enumerate() {
+                    println!("{i}: {bp}");
+                }
+            }
+            "p" | "pallets" => {
+                if print_pallets(&runtime) == 0 {
+                    println!("No pallets moving");
+                }
+            }
+            "i" | "inspect" => match arg {
+                None => {
+                    if print_bays(&runtime, None) == 0 {
+                        println!("No occupied bays");
+                    }
+                }
+                Some(a) => match parse_pos(a) {
+                    Some(pos) => {
+                        if print_bays(&runtime, Some(pos)) == 0 {
+                            println!("No live station found at {pos}");
+                        }
+                    }
+                    None => println!("Invalid location \"{a}\", expected line:col"),
+                },
+            },
+            "f" | "functions" => print_functions(&runtime),
+            "h" | "help" => println!("{HELP_MSG}"),
+            "q" | "quit" => return Ok(0),
+            _ => println!("Unknown command \"{cmd}\", type 'help' for a list of commands"),
+        }
+    }
+}
+
+/// Parses a location in the form `line:col`, as it is displayed in messages
+fn parse_pos(s: &str) -> Option<SourcePos> {
+    let (line, col) = s.split_once(':')?;
+    let line = line.parse::<usize>().ok()?;
+    let col = col.parse::<usize>().ok()?;
+    if line == 0 {
+        return None;
+    }
+    return Some(SourcePos::new(line - 1, col));
+}
+
+/// Checks whether a station's location contains a source position
+fn contains(loc: &SourceSpan, pos: SourcePos) -> bool {
+    return loc.pos.line == pos.line && loc.pos.col <= pos.col && pos.col < loc.pos.col + loc.len;
+}
+
+/// Finds the station defined at a source position
+fn find_station(program: &FSProgram, pos: SourcePos) -> Option<&Station> {
+    for f in program.function_templates.iter() {
+        for s in f.stations.iter() {
+            if contains(&s.loc, pos) {
+                return Some(s);
+            }
+        }
+    }
+    return None;
+}
+
+/// Checks whether any station triggered in the last step matches a breakpoint
+fn hit_breakpoint(program: &FSProgram, runtime: &Runtime, breakpoints: &[Breakpoint]) -> bool {
+    for firing in runtime.log.fired.iter() {
+        let station = firing.station();
+        for (i, bp) in breakpoints.iter().enumerate() {
+            let hit = match bp {
+                Breakpoint::Station(pos) => contains(&station.loc, *pos),
+                Breakpoint::Function(name) => match station.data {
+                    StationData::FunctionID(id)
+                        if station.s_type == &station::types::FUNC_INVOKE =>
+                    {
+                        program.function_templates[id].name == *name
+                    }
+                    _ => false,
+                },
+            };
+            if hit {
+                println!("Hit breakpoint {i} on {bp}");
+                return true;
+            }
+        }
+    }
+    return false;
+}
+
+/// Prints the stations triggered during the last step
+fn print_fired(runtime: &Runtime) {
+    println!("step {}:", runtime.step_count);
+    if runtime.log.fired.is_empty() {
+        println!("  no stations triggered");
+    }
+    for firing in runtime.log.fired.iter() {
+        println!("  {} in '{}'", firing.station(), firing.function.name);
+    }
+}
+
+/// Prints the pallets moving in every live function, returns the number of
+/// pallets printed
+fn print_pallets(runtime: &Runtime) -> usize {
+    let mut count = 0;
+    for f in runtime.live_frames() {
+        for (pallet, (dest, priority)) in f.moving_pallets.iter() {
+            println!(
+                "  {pallet} -> {} @ {} bay {priority} in '{}'",
+                f.stations[*dest].s_type, f.stations[*dest].loc, f.template.name
+            );
+            count += 1;
+        }
+    }
+    return count;
+}
+
+/// Prints the occupied in bays of the stations of every live function,
+/// optionally only the station at a position. Returns the number of stations
+/// printed
+fn print_bays(runtime: &Runtime, pos: Option<SourcePos>) -> usize {
+    let mut count = 0;
+    for f in runtime.live_frames() {
+        for s in f.stations.iter() {
+            let selected = match pos {
+                Some(pos) => contains(&s.loc, pos),
+                None => !s.in_bays.is_empty(),
+            };
+            if !selected {
+                continue;
+            }
+            count += 1;
+            println!("  {} in '{}':", s, f.template.name);
+            if s.in_bays.is_empty() {
+                println!("    (empty)");
+            }
+            for (priority, pallet) in s.in_bays.iter() {
+                println!("    bay {priority}: {pallet}");
+            }
+        }
+    }
+    return count;
+}
+
+/// Prints the tree of live functions
+fn print_functions(runtime: &Runtime) {
+    let mut stack: Vec<(usize, usize)> = vec![(0, 0)];
+    while let Some((id, depth)) = stack.pop() {
+        let f = runtime.frame(id);
+        println!(
+            "{: <indent$}'{}' ({} pallets moving)",
+            "",
+            f.template.name,
+            f.moving_pallets.len(),
+            indent = depth * 2 + 2,
+        );
+        for child in f.children.iter().rev() {
+            stack.push((*child, depth + 1));
+        }
+    }
+}
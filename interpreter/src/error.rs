@@ -1,9 +1,9 @@
 use std::cmp::min;
 
-use crate::util::SourceSpan;
+use crate::{util::SourceSpan, Coverage};
 
 /// Custom error handling struct
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Error {
     /// Error type
     ///
@@ -13,6 +13,14 @@ pub struct Error {
     pub loc: SourceSpan,
     /// Message
     pub msg: String,
+    /// Function invocations that led to a runtime error, most recent first
+    pub backtrace: Vec<CallSite>,
+    /// Description of the runtime state when execution stopped, if requested with
+    /// [`RunOptions::dump_state`](crate::RunOptions::dump_state)
+    pub state: Option<String>,
+    /// Coverage of the execution before the step that failed, if recording it with
+    /// [`RunOptions::coverage`](crate::RunOptions::coverage)
+    pub coverage: Option<Box<Coverage>>,
 }
 
 impl Error {
@@ -25,14 +33,36 @@ impl Error {
             t,
             loc: loc.into(),
             msg: msg.into(),
+            backtrace: Vec::new(),
+            state: None,
+            coverage: None,
+        }
+    }
+
+    pub fn with_backtrace(self, backtrace: Vec<CallSite>) -> Self {
+        Error { backtrace, ..self }
+    }
+
+    pub fn with_state(self, state: String) -> Self {
+        Error {
+            state: Some(state),
+            ..self
+        }
+    }
+
+    pub fn with_coverage(self, coverage: Coverage) -> Self {
+        Error {
+            coverage: Some(Box::new(coverage)),
+            ..self
         }
     }
 
     /// function for generating a pretty error message
+    #[allow(clippy::ptr_arg)]
     pub fn pretty_msg(&self, src: &String) -> String {
         // don't print source location if location is zero
         if self.loc.len == 0 {
-            return format!("{}: {}", self.t, self.msg);
+            return format!("{}: {}{}", self.t, self.msg, self.backtrace_msg());
         }
 
         // generating 2d vector layout of source code
@@ -101,17 +131,63 @@ impl Error {
 
         output += "\x1b[22m\n";
         output += self.msg.as_str();
+        output += self.backtrace_msg().as_str();
+        return output;
+    }
+
+    /// function for generating the list of invocations that led to the error, long
+    /// backtraces are shortened in the middle
+    fn backtrace_msg(&self) -> String {
+        if self.backtrace.is_empty() {
+            return String::new();
+        }
+        let mut output = String::from("\nBacktrace (most recent invocation first):");
+        let len = self.backtrace.len();
+        for (i, call) in self.backtrace.iter().enumerate() {
+            if len > 20 && i >= 10 && i < len - 10 {
+                if i == 10 {
+                    output += format!("\n  ... {} more invocations", len - 20).as_str();
+                }
+                continue;
+            }
+            output += format!("\n  {call}").as_str();
+        }
         return output;
     }
 }
 
+/// An invocation of a function, part of an error's backtrace
+#[derive(Debug, Clone)]
+pub struct CallSite {
+    /// Name of the invoked function
+    pub function: String,
+    /// Name of the function the invoking station is in
+    pub caller: String,
+    /// Location of the invoking station
+    pub loc: SourceSpan,
+}
+impl std::fmt::Display for CallSite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' invoked @ {} in '{}'",
+            self.function, self.loc, self.caller
+        )
+    }
+}
+
 /// Types of handled errors
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorType {
     SyntaxError,
     ConveyorBeltError,
     IdentifierError,
     RuntimeError,
+    /// A step or time limit was hit, or execution was interrupted, before the
+    /// program finished
+    LimitError,
+    /// A likely mistake that doesn't stop the program from running
+    Warning,
 }
 impl std::fmt::Display for ErrorType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -120,6 +196,8 @@ impl std::fmt::Display for ErrorType {
             Self::ConveyorBeltError => "Conveyor Belt Error",
             Self::IdentifierError => "Identifier Error",
             Self::RuntimeError => "Runtime Error",
+            Self::LimitError => "Limit Error",
+            Self::Warning => "Warning",
         };
         write!(f, "{s}")
     }
@@ -1,4 +1,7 @@
-use crate::{station::*, *};
+use std::time::{Duration, Instant};
+
+use crate::{runtime::*, station::*, *};
+use station::types::Context;
 
 /// Template of a function, used to instantiate a function when it is invoked
 #[derive(Clone)]
@@ -24,45 +27,88 @@ pub struct Function<'a> {
     /// Parent program
     program: &'a FSProgram,
     /// Stations local to this function
-    stations: Vec<Station>,
+    pub(crate) stations: Vec<Station>,
     /// Vector of all pallets to move in the next step, contains tuple with the pallet and the destination index and bay priority
-    moving_pallets: Vec<(Pallet, (usize, u32))>,
-    /// All functions spawned from this one
-    children: Vec<Function<'a>>,
+    pub(crate) moving_pallets: Vec<(Pallet, (usize, u32))>,
+    /// Frame IDs of all functions spawned from this one
+    pub(crate) children: Vec<usize>,
+    /// Functions invoked during the last step, waiting to be given a frame
+    pub(crate) spawned: Vec<Function<'a>>,
+    /// Indices of stations whose in bays have changed since they were last checked
+    pending: Vec<usize>,
+    /// Indices of stations that are ready without any inputs, these are checked
+    /// every step
+    idle_ready: Vec<usize>,
+    /// Whether each station has been triggered
+    pub(crate) fired: Vec<bool>,
     /// Output of this function
-    output: Option<Pallet>,
+    pub(crate) output: Option<Pallet>,
     /// Index of station that invoked this function
-    parent_station: usize,
+    pub(crate) parent_station: usize,
     /// Recursion depth
-    depth: u32,
+    pub(crate) depth: u32,
 }
 impl<'a> Function<'a> {
-    /// Performs one time step (returns true if an exit station was triggered)
-    pub fn step(&mut self) -> Result<bool, Error> {
+    /// Performs one time step of this function's own stations (returns the exit
+    /// status if an exit station was triggered), recording what happened in `log`.
+    /// Invoked functions are put in `spawned`, stepping children is left to the
+    /// runtime
+    pub fn step(
+        &mut self,
+        log: &mut StepLog<'a>,
+        max_depth: Option<u32>,
+        ctx: &mut Context,
+    ) -> Result<Option<u8>, Error> {
         // moving the pallets
         for (pallet, (dest_i, priority)) in self.moving_pallets.iter() {
             self.stations[*dest_i].send_pallet(pallet.clone(), *priority);
+            self.pending.push(*dest_i);
+            if log.detailed {
+                log.moved.push(Move {
+                    function: self.template,
+                    pallet: pallet.clone(),
+                    dest: (*dest_i, *priority),
+                });
+            }
         }
         self.moving_pallets.clear();
 
-        // executing station procedures
-        for i in 0..self.stations.len() {
+        // executing station procedures, only stations that received pallets can
+        // become ready. They are checked in index order, as if every station was
+        // scanned
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.extend_from_slice(&self.idle_ready);
+        pending.sort_unstable();
+        pending.dedup();
+        for &i in pending.iter() {
             let station = &mut self.stations[i];
 
-            if !station.ready(&self.program) {
+            if !station.ready(self.program) {
                 continue; // not enough inputs to trigger procedure
             }
             let input: Vec<Pallet> = station.get_input_pallets();
+            self.fired[i] = true;
+            let mut firing = Firing {
+                function: self.template,
+                station: i,
+                inputs: Vec::new(),
+                output: None,
+                time: Duration::ZERO,
+            };
+            if log.detailed {
+                firing.inputs = input.clone();
+            }
 
-            if station.s_type == &station::types::FUNC_INVOKE {
+            let output: Option<Pallet> = if station.s_type == &station::types::FUNC_INVOKE {
                 // special case: function invocation
                 let function_template = if let StationData::FunctionID(id) = station.data {
-                    if self.depth >= MAX_RECURSION_DEPTH {
+                    if max_depth.is_some_and(|max| self.depth >= max) {
                         return Err(Error::new(
                             RuntimeError,
                             station.loc,
                             format!(
-                                "Max recursion depth hit during invocation of function '{}'",
+                                "Max recursion depth of {} hit during invocation of function '{}'",
+                                max_depth.unwrap(),
                                 self.program.function_templates[id].name
                             ),
                         ));
@@ -73,67 +119,95 @@ impl<'a> Function<'a> {
                 };
 
                 self.invoke(function_template, input, i);
-                continue;
+                if log.emits {
+                    for (j, s) in function_template.stations.iter().enumerate() {
+                        if let StationData::FunctionIDAndIndex(_, _) = s.data {
+                            log.emitted.push(Emit {
+                                function: function_template,
+                                station: j,
+                            });
+                        }
+                    }
+                }
+                None
             } else if station.s_type == &station::types::FUNC_OUTPUT {
                 // special case: function output
                 self.output = Some(input[0].clone());
-                continue;
+                None
             } else if station.s_type == &station::types::ASSIGN {
                 // special case: assign station
                 if let StationData::AssignValue(p) = &station.data {
-                    for out_bay in station.out_bays.iter() {
-                        self.moving_pallets.push((p.clone(), *out_bay));
-                    }
+                    Some(p.clone())
                 } else {
                     return Err(Error::new(
                         RuntimeError,
                         station.loc,
-                        format!("Can't fetch assign value"),
+                        "Can't fetch assign value",
                     ));
-                };
-                continue;
+                }
             } else if station.s_type == &station::types::EXIT {
-                // special case: exit station
-                return Ok(true);
-            }
-
-            // running procedures
-            match (station.s_type.procedure)(input) {
-                Ok(Some(p)) => {
-                    debug_assert!(station.s_type.output == true, "Unexpected pallet returned");
-                    for out_bay in station.out_bays.iter() {
-                        self.moving_pallets.push((p.clone(), *out_bay));
-                    }
+                // special case: exit station, an integer pallet is the exit
+                // status and any other pallet exits successfully
+                let status = match input[0] {
+                    Pallet::Int(i) => match u8::try_from(i) {
+                        Ok(status) => status,
+                        Err(_) => {
+                            return Err(Error::new(
+                                RuntimeError,
+                                station.loc,
+                                format!("Exit status must be between 0 and 255, received {i}"),
+                            ));
+                        }
+                    },
+                    _ => 0,
+                };
+                log.fired.push(firing);
+                return Ok(Some(status));
+            } else {
+                // running procedures
+                let start_time = log.timed.then(Instant::now);
+                let res = station.s_type.procedure.call(input, ctx);
+                if let Some(start_time) = start_time {
+                    firing.time = start_time.elapsed();
                 }
-                Ok(None) => {}
-                Err(msg) => {
-                    return Err(Error::new(RuntimeError, station.loc, msg));
+                match res {
+                    Ok(Some(_)) if !station.s_type.output => {
+                        return Err(Error::new(
+                            RuntimeError,
+                            station.loc,
+                            format!("Station '{}' produced an unexpected pallet", station.s_type),
+                        ));
+                    }
+                    Ok(p) => p,
+                    Err(msg) => {
+                        return Err(Error::new(RuntimeError, station.loc, msg));
+                    }
                 }
-            }
-        }
+            };
 
-        // stepping children
-        for child in self.children.iter_mut() {
-            if child.step()? {
-                return Ok(true);
-            }
-            // spawning children's output
-            if let Some(output) = child.output.clone() {
-                for dest in self.stations[child.parent_station].out_bays.iter() {
-                    self.moving_pallets.push((output.clone(), *dest));
+            if let Some(p) = &output {
+                for out_bay in self.stations[i].out_bays.iter() {
+                    self.moving_pallets.push((p.clone(), *out_bay));
+                }
+                if log.emits {
+                    log.emitted.push(Emit {
+                        function: self.template,
+                        station: i,
+                    });
                 }
             }
+            if log.detailed {
+                firing.output = output;
+            }
+            log.fired.push(firing);
         }
+        pending.clear();
+        self.pending = pending;
 
-        // checking if children are done executing
-        self.children.retain(|child| {
-            return !child.is_done();
-        });
-
-        return Ok(false);
+        return Ok(None);
     }
 
-    /// Instantiates a function template as a child
+    /// Instantiates a function template as a child, to be spawned by the runtime
     pub fn invoke(
         &mut self,
         template: &'a FunctionTemplate,
@@ -145,16 +219,7 @@ impl<'a> Function<'a> {
             template.n_args,
             "Not provided enough inputs to func_invoke"
         );
-        let mut f = Function {
-            template,
-            program: self.program,
-            stations: template.stations.clone(),
-            moving_pallets: Vec::new(),
-            children: Vec::new(),
-            output: None,
-            depth: self.depth + 1,
-            parent_station,
-        };
+        let mut f = Function::new(template, self.program, self.depth + 1, parent_station);
         for s in f.stations.iter() {
             if let StationData::FunctionIDAndIndex(_, arg_index) = s.data {
                 // station is function input
@@ -163,7 +228,7 @@ impl<'a> Function<'a> {
                 }
             }
         }
-        self.children.push(f);
+        self.spawned.push(f);
     }
 
     /// Returns whether or not this function is done executing
@@ -171,19 +236,36 @@ impl<'a> Function<'a> {
         return self.moving_pallets.is_empty() && self.children.is_empty();
     }
 
-    /// Instantiates the main function, used for program initialization
-    pub fn instantiate_main(program: &'a FSProgram) -> Self {
-        let template = &program.main;
-        let mut f = Function {
+    /// Creates a new instance of a function template with no moving pallets
+    fn new(
+        template: &'a FunctionTemplate,
+        program: &'a FSProgram,
+        depth: u32,
+        parent_station: usize,
+    ) -> Self {
+        let stations = template.stations.clone();
+        let idle_ready = (0..stations.len())
+            .filter(|i| stations[*i].ready(program))
+            .collect();
+        return Function {
             template,
             program,
+            stations,
             moving_pallets: Vec::new(),
-            stations: template.stations.clone(),
             children: Vec::new(),
+            spawned: Vec::new(),
+            pending: Vec::new(),
+            idle_ready,
+            fired: vec![false; template.stations.len()],
             output: None,
-            depth: 0,
-            parent_station: 0,
+            depth,
+            parent_station,
         };
+    }
+
+    /// Instantiates the main function, used for program initialization
+    pub fn instantiate_main(program: &'a FSProgram) -> Self {
+        let mut f = Function::new(&program.main, program, 0, 0);
 
         // spawning start pallets
         for s in f.stations.iter() {
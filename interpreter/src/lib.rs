@@ -1,65 +1,243 @@
-use std::time::Instant;
+use std::{
+    io::{stdin, stdout, BufRead, BufReader, Write},
+    sync::{atomic::AtomicBool, Arc},
+    time::{Duration, Instant},
+};
 
+pub mod coverage;
+pub mod debugger;
 pub mod error;
 pub mod function;
 pub mod pallet;
+pub mod profile;
 pub mod station;
+pub mod trace;
 pub mod util;
 
 mod preprocessor;
 mod runtime;
 
+pub use coverage::Coverage;
 pub use error::{
-    Error,
+    CallSite, Error,
     ErrorType::{self, *},
 };
 use function::FunctionTemplate;
 pub use pallet::Pallet;
+pub use profile::Profile;
+pub use station::{
+    registry::Registry,
+    types::{Context, StationType},
+};
 
-pub static MAX_RECURSION_DEPTH: u32 = 1000;
-pub static mut COLOR_OUTPUT: bool = false;
-pub static mut DEBUG_LEVEL: u8 = 0;
+/// Preprocesses a program so that it can be executed any number of times, returns
+/// every error found if it is invalid
+pub fn load(src: &str, options: &RunOptions) -> Result<FSProgram, Vec<Error>> {
+    return load_with_registry(src, &Registry::new(), options);
+}
 
-pub fn run(src: &str, print_benchmark: bool) -> Result<(), Error> {
+/// Preprocesses a program that may use station types from the registry, so that
+/// it can be executed any number of times
+pub fn load_with_registry(
+    src: &str,
+    registry: &Registry,
+    options: &RunOptions,
+) -> Result<FSProgram, Vec<Error>> {
     let start_time = Instant::now();
+    let mut program = preprocessor::process(src, registry, options)?;
+    program.preprocess_time = start_time.elapsed();
+    return Ok(program);
+}
 
-    let mut program = preprocessor::process(src)?;
-
-    program.benchmark = print_benchmark;
+/// Preprocesses and executes a program, returns every preprocessing error or the
+/// runtime error that stopped it
+pub fn run(src: &str, options: &mut RunOptions) -> Result<RunReport, Vec<Error>> {
+    let program = load(src, options)?;
+    return program.run(options).map_err(|e| vec![e]);
+}
 
-    let runtime_start_time = Instant::now();
-    let (res, step_count) = runtime::execute(&program);
-    if res.is_err() {
-        return res;
+/// Options that control how a program is preprocessed and executed
+pub struct RunOptions<'io> {
+    /// Debug logging level, 0 for no logging
+    pub debug_level: u8,
+    /// Whether to use colored terminal output
+    pub color: bool,
+    /// Whether to write benchmarking information to the output after completion
+    pub benchmark: bool,
+    /// Maximum number of steps to execute before aborting with a `LimitError`
+    pub max_steps: Option<u64>,
+    /// Maximum wall-clock time to execute for before aborting with a `LimitError`
+    pub timeout: Option<Duration>,
+    /// Maximum depth of nested function invocations, unlimited if `None`
+    pub max_depth: Option<u32>,
+    /// Arguments passed to the program, read with the `argc` and `arg` stations
+    pub args: Vec<String>,
+    /// Flag that stops execution with a `LimitError` when it is set, from another
+    /// thread or a signal handler
+    pub interrupt: Option<Arc<AtomicBool>>,
+    /// Whether to describe the runtime state in the error when execution stops
+    /// with one (see [`Error::state`])
+    pub dump_state: bool,
+    /// How to report work left unfinished when the program ends
+    pub stall_check: StallCheck,
+    /// What integer arithmetic does when its result doesn't fit in an integer pallet
+    pub overflow: Overflow,
+    /// Whether to count and time station triggers and function invocations (see
+    /// [`RunReport::profile`])
+    pub profile: bool,
+    /// Whether to record which stations triggered and which belts carried pallets
+    /// (see [`RunReport::coverage`], and [`Error::coverage`] if execution fails)
+    pub coverage: bool,
+    /// Where the program reads input from
+    pub input: Box<dyn BufRead + 'io>,
+    /// Where the program writes output to
+    pub output: Box<dyn Write + 'io>,
+    /// Where to write an execution trace to, if anywhere (see [`trace::write_step`])
+    pub trace: Option<Box<dyn Write + 'io>>,
+}
+impl Default for RunOptions<'_> {
+    /// No logging or limits, using stdin and stdout
+    fn default() -> Self {
+        RunOptions {
+            debug_level: 0,
+            color: false,
+            benchmark: false,
+            max_steps: None,
+            timeout: None,
+            max_depth: None,
+            args: Vec::new(),
+            interrupt: None,
+            dump_state: false,
+            stall_check: StallCheck::Off,
+            overflow: Overflow::Error,
+            profile: false,
+            coverage: false,
+            input: Box::new(BufReader::new(stdin())),
+            output: Box::new(stdout()),
+            trace: None,
+        }
     }
+}
 
-    if print_benchmark {
-        let end_time = Instant::now();
-        let preprocess_duration: f64 =
-            ((runtime_start_time - start_time).as_nanos() as f64) / 1000000000f64;
-        let runtime_duration: f64 =
-            ((end_time - runtime_start_time).as_nanos() as f64) / 1000000000f64;
-        let total_duration: f64 = ((end_time - start_time).as_nanos() as f64) / 1000000000f64;
+/// How to report work left unfinished when a program ends without an exit station:
+/// pallets stranded in in bays, stations that were partly fed but never triggered,
+/// and functions that never produced an output for an invocation whose output is
+/// used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StallCheck {
+    /// Don't check
+    Off,
+    /// Report them as warnings in the [`RunReport`]
+    Warn,
+    /// Stop with a runtime error describing them
+    Error,
+}
+
+/// What integer arithmetic stations do when their result is out of the range of
+/// an integer pallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Stop with a runtime error at the station
+    Error,
+    /// Wrap around to the other end of the range
+    Wrapping,
+    /// Clamp to the smallest or largest integer
+    Saturating,
+}
+
+/// Information about a completed execution of a program
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    /// Number of steps executed
+    pub steps: u64,
+    /// Whether the program was ended by an exit station
+    pub exited: bool,
+    /// Exit status given to the exit station, 0 if the program ended normally
+    pub exit_status: u8,
+    /// Time spent preprocessing the program
+    pub preprocess_time: Duration,
+    /// Time spent executing the program
+    pub runtime_time: Duration,
+    /// Work left unfinished when the program ended (see [`StallCheck`])
+    pub warnings: Vec<Error>,
+    /// How often each station triggered and each function was invoked, if
+    /// profiling
+    pub profile: Option<Profile>,
+    /// Which stations triggered and which belts carried pallets, if recording
+    /// coverage
+    pub coverage: Option<Coverage>,
+}
+impl RunReport {
+    /// Writes the benchmarking information
+    pub fn write_benchmark(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        let preprocess_duration = self.preprocess_time.as_secs_f64();
+        let runtime_duration = self.runtime_time.as_secs_f64();
+        let total_duration = preprocess_duration + runtime_duration;
 
         let avg_step_duration =
-            ((end_time - runtime_start_time).as_nanos() as f64) / ((1000 * step_count) as f64);
-
-        println!("\n======Benchmark======");
-        println!(" steps      {}", step_count);
-        println!(" avg step   {:.2}ms", avg_step_duration);
-        println!();
-        println!(" preprocess {:.5}s", preprocess_duration);
-        println!(" runtime    {:.5}s", runtime_duration);
-        println!(" total      {:.5}s", total_duration);
-        println!("=====================");
-    }
+            (self.runtime_time.as_nanos() as f64) / ((1000 * self.steps) as f64);
 
-    Ok(())
+        writeln!(out, "\n======Benchmark======")?;
+        writeln!(out, " steps      {}", self.steps)?;
+        writeln!(out, " avg step   {:.2}ms", avg_step_duration)?;
+        writeln!(out)?;
+        writeln!(out, " preprocess {:.5}s", preprocess_duration)?;
+        writeln!(out, " runtime    {:.5}s", runtime_duration)?;
+        writeln!(out, " total      {:.5}s", total_duration)?;
+        writeln!(out, "=====================")?;
+        return Ok(());
+    }
 }
 
 /// Represents the output of the preprocessor/input to the runtime module
 pub struct FSProgram {
     main: FunctionTemplate,
     function_templates: Vec<FunctionTemplate>,
-    benchmark: bool,
+    /// Likely mistakes found during preprocessing
+    warnings: Vec<Error>,
+    /// Time it took to preprocess this program
+    preprocess_time: Duration,
+}
+impl FSProgram {
+    /// Gets the warnings found during preprocessing, these are likely mistakes that
+    /// don't stop the program from running
+    pub fn warnings(&self) -> &[Error] {
+        return &self.warnings;
+    }
+
+    /// Executes the program with the input, output and limits in `options`
+    pub fn run(&self, options: &mut RunOptions) -> Result<RunReport, Error> {
+        let start_time = Instant::now();
+        let (res, steps) = runtime::execute(self, options);
+        let outcome = res?;
+
+        let report = RunReport {
+            steps,
+            exited: outcome.exit_status.is_some(),
+            exit_status: outcome.exit_status.unwrap_or(0),
+            preprocess_time: self.preprocess_time,
+            runtime_time: start_time.elapsed(),
+            warnings: outcome.warnings,
+            profile: outcome.profile,
+            coverage: outcome.coverage,
+        };
+        if options.benchmark {
+            let _ = report.write_benchmark(&mut options.output);
+        }
+        let _ = options.output.flush();
+        return Ok(report);
+    }
+
+    /// Gets the index of a function template, main's template is a copy of the
+    /// first one
+    pub(crate) fn template_index(&self, f: &FunctionTemplate) -> usize {
+        if std::ptr::eq(f, &self.main) {
+            return 0;
+        }
+        return self
+            .function_templates
+            .iter()
+            .position(|other| std::ptr::eq(f, other))
+            .expect("Function template is not in the program");
+    }
 }
@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{collections::BTreeMap, fmt};
 
 pub mod constants;
 
@@ -11,20 +11,74 @@ pub enum Pallet {
     String(String),
     Int(i64),
     Float(f64),
+    /// Stack of pallets, in order from bottom to top
+    List(Vec<Pallet>),
+    /// Pallets keyed by scalar pallets, in order of their keys
+    Map(BTreeMap<MapKey, Pallet>),
+}
+impl Pallet {
+    /// Formats the pallet's type and value, without the `Pallet<>` wrapper
+    fn contents(&self) -> String {
+        return match self {
+            Pallet::Empty => String::from(" "),
+            Pallet::Bool(b) => format!("b:{}", if *b { "true" } else { "false" }),
+            Pallet::Char(c) => format!("c:\'{}\'", c),
+            Pallet::String(s) => format!("s:\"{}\"", s),
+            Pallet::Int(i) => format!("i:{}", i),
+            Pallet::Float(f) => format!("f:{}", f),
+            Pallet::List(l) => {
+                let items: Vec<String> = l.iter().map(|p| p.contents()).collect();
+                format!("l:[{}]", items.join(", "))
+            }
+            Pallet::Map(m) if m.is_empty() => String::from("m:[:]"),
+            Pallet::Map(m) => {
+                let items: Vec<String> = m
+                    .iter()
+                    .map(|(k, v)| {
+                        format!("{}: {}", Pallet::from(k.clone()).contents(), v.contents())
+                    })
+                    .collect();
+                format!("m:[{}]", items.join(", "))
+            }
+        };
+    }
 }
 impl fmt::Display for Pallet {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Pallet::Empty => String::from("Pallet< >"),
-                Pallet::Bool(b) => format!("Pallet<b:{}>", if *b { "true" } else { "false" }),
-                Pallet::Char(c) => format!("Pallet<c:\'{}\'>", c),
-                Pallet::String(s) => format!("Pallet<s:\"{}\">", s),
-                Pallet::Int(i) => format!("Pallet<i:{}>", i),
-                Pallet::Float(f) => format!("Pallet<f:{}>", f),
-            },
-        )
+        write!(f, "Pallet<{}>", self.contents())
+    }
+}
+
+/// Scalar pallet that can be used as a key in a map pallet
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MapKey {
+    Bool(bool),
+    Char(char),
+    String(String),
+    Int(i64),
+}
+impl TryFrom<Pallet> for MapKey {
+    type Error = String;
+
+    fn try_from(p: Pallet) -> Result<Self, Self::Error> {
+        return match p {
+            Pallet::Bool(b) => Ok(MapKey::Bool(b)),
+            Pallet::Char(c) => Ok(MapKey::Char(c)),
+            Pallet::String(s) => Ok(MapKey::String(s)),
+            Pallet::Int(i) => Ok(MapKey::Int(i)),
+            p => Err(format!(
+                "{p} can't be a map key, keys must be boolean, character, string or integer pallets"
+            )),
+        };
+    }
+}
+impl From<MapKey> for Pallet {
+    fn from(k: MapKey) -> Self {
+        return match k {
+            MapKey::Bool(b) => Pallet::Bool(b),
+            MapKey::Char(c) => Pallet::Char(c),
+            MapKey::String(s) => Pallet::String(s),
+            MapKey::Int(i) => Pallet::Int(i),
+        };
     }
 }
@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+
+use crate::{
+    station::{types, Station, StationData},
+    *,
+};
+
+/// Looks for stations that are valid but can't do anything useful, these are
+/// returned as warnings:
+/// - stations that no entry station leads to
+/// - stations without an output that have outgoing belts
+/// - stations whose output goes nowhere
+/// - stations that can never receive enough inputs to trigger
+pub fn check_stations(functions: &[FunctionTemplate], unreachable: &[Station]) -> Vec<Error> {
+    let mut warnings: Vec<Error> = Vec::new();
+
+    for s in unreachable.iter() {
+        warnings.push(Error::new(
+            Warning,
+            s.loc,
+            format!(
+                "Station '{}' is unreachable, no belts lead to it from [main] or a function input",
+                s.s_type
+            ),
+        ));
+    }
+
+    for f in functions.iter() {
+        // in bay priorities that belts lead to, for every station
+        let mut in_bays: Vec<HashSet<u32>> = vec![HashSet::new(); f.stations.len()];
+        for s in f.stations.iter() {
+            for (dest, priority) in s.out_bays.iter() {
+                in_bays[*dest].insert(*priority);
+            }
+        }
+
+        for (i, s) in f.stations.iter().enumerate() {
+            // outputs
+            if !s.out_bays.is_empty() {
+                if !s.s_type.output || s.s_type == &types::FUNC_OUTPUT {
+                    warnings.push(Error::new(
+                        Warning,
+                        s.loc,
+                        format!(
+                            "Station '{}' doesn't output pallets, its outgoing belts are never used",
+                            s.s_type
+                        ),
+                    ));
+                }
+            } else if s.s_type.output && s.s_type != &types::FUNC_OUTPUT {
+                // the output of a function without an output station isn't expected
+                // to go anywhere
+                let produces_output = match s.data {
+                    StationData::FunctionID(id) if s.s_type == &types::FUNC_INVOKE => {
+                        has_output(&functions[id])
+                    }
+                    _ => true,
+                };
+                if produces_output {
+                    warnings.push(Error::new(
+                        Warning,
+                        s.loc,
+                        format!("Output of station '{}' goes nowhere", s.s_type),
+                    ));
+                }
+            }
+
+            // inputs
+            if s.s_type == &types::MAIN || s.s_type == &types::FUNC_INPUT {
+                continue;
+            }
+            let needed = match s.data {
+                StationData::FunctionID(id) if s.s_type == &types::FUNC_INVOKE => {
+                    functions[id].n_args
+                }
+                _ => s.s_type.inputs,
+            };
+            if in_bays[i].len() < needed {
+                warnings.push(Error::new(
+                    Warning,
+                    s.loc,
+                    format!(
+                        "Station '{}' needs {needed} input(s) to trigger, but only {} belt(s) lead to it",
+                        s.s_type,
+                        in_bays[i].len()
+                    ),
+                ));
+            }
+        }
+    }
+
+    return warnings;
+}
+
+/// Checks that every function is defined with inputs numbered from 0 without gaps,
+/// pushing errors to `errors`. Returns warnings for functions that are never
+/// invoked, and for invocations that use the result of a function without an
+/// output station
+pub fn check_functions(functions: &[FunctionTemplate], errors: &mut Vec<Error>) -> Vec<Error> {
+    let mut warnings: Vec<Error> = Vec::new();
+
+    // argument indices
+    for f in functions.iter() {
+        let mut inputs: Vec<(usize, &Station)> = f
+            .stations
+            .iter()
+            .filter_map(|s| match s.data {
+                StationData::FunctionIDAndIndex(_, arg_i) => Some((arg_i, s)),
+                _ => None,
+            })
+            .collect();
+        inputs.sort_by_key(|(arg_i, _)| *arg_i);
+        let mut expected = 0;
+        for (arg_i, s) in inputs {
+            if arg_i > expected {
+                errors.push(Error::new(
+                    SyntaxError,
+                    s.loc,
+                    format!(
+                        "Function '{}' is missing argument {expected}, arguments must be numbered from 0 without gaps",
+                        f.name
+                    ),
+                ));
+            }
+            expected = arg_i + 1;
+        }
+    }
+
+    // invocations
+    let mut invoked: Vec<bool> = vec![false; functions.len()];
+    for f in functions.iter() {
+        for s in f.stations.iter() {
+            let id = match s.data {
+                StationData::FunctionID(id) if s.s_type == &types::FUNC_INVOKE => id,
+                _ => continue,
+            };
+            invoked[id] = true;
+            let template = &functions[id];
+            if template.stations.is_empty() {
+                errors.push(Error::new(
+                    SyntaxError,
+                    s.loc,
+                    format!(
+                        "Function '{}' is never defined, it has no input stations",
+                        template.name
+                    ),
+                ));
+            } else if !s.out_bays.is_empty() && !has_output(template) {
+                warnings.push(Error::new(
+                    Warning,
+                    s.loc,
+                    format!(
+                        "Function '{}' has no output station, the result of this invocation is never produced",
+                        template.name
+                    ),
+                ));
+            }
+        }
+    }
+
+    // the main function is never invoked
+    for (f, invoked) in functions.iter().zip(invoked).skip(1) {
+        if invoked {
+            continue;
+        }
+        if let Some(s) = f.stations.iter().find(|s| s.s_type == &types::FUNC_INPUT) {
+            warnings.push(Error::new(
+                Warning,
+                s.loc,
+                format!("Function '{}' is defined but never invoked", f.name),
+            ));
+        }
+    }
+
+    return warnings;
+}
+
+/// Checks whether a function has an output station
+fn has_output(f: &FunctionTemplate) -> bool {
+    return f.stations.iter().any(|s| s.s_type == &types::FUNC_OUTPUT);
+}
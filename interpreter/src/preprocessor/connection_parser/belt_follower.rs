@@ -4,6 +4,7 @@ use crate::{preprocessor::*, util::Direction::*, *};
 /// if it is find the destination
 ///
 /// Returns an optional tuple of the destination index and in bay priority if it is an output bay
+#[allow(clippy::ptr_arg, clippy::if_same_then_else)]
 pub fn follow_belt(
     map: &Vec<Vec<char>>,
     stations: &Vec<Station>,
@@ -111,6 +112,9 @@ pub fn follow_belt(
                     break;
                 }
                 pos.line -= 1;
+                if pos.col >= map[pos.line].len() {
+                    break;
+                }
             }
             EAST => {
                 pos.col += 1;
@@ -120,7 +124,7 @@ pub fn follow_belt(
             }
             SOUTH => {
                 pos.line += 1;
-                if pos.line >= map.len() {
+                if pos.line >= map.len() || pos.col >= map[pos.line].len() {
                     break;
                 }
             }
@@ -134,15 +138,16 @@ pub fn follow_belt(
         // moving
         c = map[pos.line][pos.col];
     }
-    // dangling belt out of bounds
+    // dangling belt out of bounds, pointing at its last character
     return Err(Error::new(
         SyntaxError,
-        SourceSpan::new(SourcePos::new(pos.line, pos.col), 1),
+        SourceSpan::new(last_pos, 1),
         "Unattached conveyor belt",
     ));
 }
 
 /// Gets the neighboring locations of a specific station in order of highest priority
+#[allow(clippy::ptr_arg)]
 pub fn get_neighbors_inorder(
     map: &Vec<Vec<char>>,
     station: &Station,
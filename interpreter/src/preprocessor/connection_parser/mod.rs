@@ -10,12 +10,17 @@ use crate::{
     *,
 };
 
-/// Parses conveyor belts in the character map, connects the stations, and moves them into their function templates
+/// Parses conveyor belts in the character map, connects the stations, and moves them into their function templates.
+/// Errors are pushed to `errors`, and the connections they were found on are skipped.
+/// Returns the stations that no entry station leads to, which are left out of every function template
+#[allow(clippy::ptr_arg)]
 pub fn parse(
     char_map: &Vec<Vec<char>>,
     stations: Vec<Station>,
     functions: &mut Vec<FunctionTemplate>,
-) -> Result<(), Error> {
+    options: &RunOptions,
+    errors: &mut Vec<Error>,
+) -> Vec<Station> {
     let mut stations = stations;
     // hashmap to keep track of which stations have been visited, and by what function id
     let mut visited_stations: HashMap<usize, usize> = HashMap::with_capacity(stations.len());
@@ -39,12 +44,11 @@ pub fn parse(
         let mut to_visit: Vec<usize> = Vec::new();
         to_visit.push(entry_index);
 
-        while !to_visit.is_empty() {
-            let i = to_visit.pop().unwrap();
+        while let Some(i) = to_visit.pop() {
             if visited_stations.contains_key(&i) {
                 // station has been visited already
                 if *visited_stations.get(&i).unwrap() != cur_function_id {
-                    return Err(Error::new(
+                    errors.push(Error::new(
                         SyntaxError,
                         stations[i].loc,
                         format!(
@@ -61,17 +65,21 @@ pub fn parse(
 
             let neighbors = get_neighbors(char_map, &stations[i]);
             for neighbor in neighbors {
-                let (dest, priority) = match follow_belt(char_map, &stations, neighbor)? {
-                    Some(res) => res,
-                    None => {
+                let (dest, priority) = match follow_belt(char_map, &stations, neighbor) {
+                    Ok(Some(res)) => res,
+                    Ok(None) => {
                         // neighbor position isn't a conveyor belt
                         continue;
                     }
+                    Err(e) => {
+                        errors.push(e);
+                        continue;
+                    }
                 };
                 if stations[dest].s_type == &station::types::FUNC_OUTPUT {
                     if let StationData::FunctionID(id) = stations[dest].data {
                         if id != cur_function_id {
-                            return Err(Error::new(
+                            errors.push(Error::new(
                                 SyntaxError,
                                 stations[dest].loc,
                                 format!(
@@ -79,6 +87,7 @@ pub fn parse(
                                     functions[id].name, functions[cur_function_id].name
                                 ),
                             ));
+                            continue;
                         }
                     }
                 }
@@ -92,16 +101,23 @@ pub fn parse(
     // used to map old global indices of stations to function-local indices
     let mut index_mappings: HashMap<usize, usize> = HashMap::new();
 
-    // moving every station into its proper function template
+    // moving every station into its proper function template, in source order so
+    // that station indices are the same on every run
+    let unreachable: Vec<Station> = (0..stations.len())
+        .filter(|i| !visited_stations.contains_key(i))
+        .map(|i| stations[i].clone())
+        .collect();
+    let mut visited_stations: Vec<(usize, usize)> = visited_stations.into_iter().collect();
+    visited_stations.sort_unstable();
     for (i, function_id) in visited_stations {
         index_mappings.insert(i, functions[function_id].stations.len());
         functions[function_id].stations.push(stations[i].clone());
     }
 
     for (i, f) in functions.iter_mut().enumerate() {
-        debug!(4, "function {i} '{}':", f.name);
+        debug!(options, 4, "function {i} '{}':", f.name);
         for s in f.stations.iter_mut() {
-            debug!(4, " - {s}");
+            debug!(options, 4, " - {s}");
             for (dest, _) in s.out_bays.iter_mut() {
                 // updating connection indices
                 if let Some(new_i) = index_mappings.get(dest) {
@@ -111,10 +127,11 @@ pub fn parse(
         }
     }
 
-    return Ok(());
+    return unreachable;
 }
 
 /// Gets the neighboring locations (in no particular order)
+#[allow(clippy::ptr_arg)]
 pub fn get_neighbors(map: &Vec<Vec<char>>, station: &Station) -> Vec<(SourcePos, Direction)> {
     let mut neighbors: Vec<(SourcePos, Direction)> = Vec::new();
 
@@ -1,11 +1,16 @@
-use std::{cmp, collections::HashSet};
+use std::{cmp, collections::HashSet, time::Duration};
 
+mod analysis;
 pub mod connection_parser;
 pub mod station_parser;
 
 use station::StationData;
 
-use crate::{station::Station, util::*, *};
+use crate::{
+    station::{registry::Registry, Station},
+    util::*,
+    *,
+};
 
 pub const BELT_CHARS: &str = "─│┌┐└┘═║╔╗╚╝";
 #[allow(dead_code)]
@@ -16,9 +21,14 @@ pub const EAST_BELT_CHARS: &str = "─┌└═╔╚";
 pub const SOUTH_BELT_CHARS: &str = "│┌┐║╔╗";
 pub const WEST_BELT_CHARS: &str = "─┐┘═╗╝";
 
-/// Preprocesses a source string, validating/parsing the syntax and grammar
-pub fn process<'a>(src: &str) -> Result<FSProgram, Error> {
-    debug!(2, "Starting preprocessing");
+/// Preprocesses a source string, validating/parsing the syntax and grammar. Every
+/// error found is returned, not only the first
+pub fn process(
+    src: &str,
+    registry: &Registry,
+    options: &RunOptions,
+) -> Result<FSProgram, Vec<Error>> {
+    debug!(options, 2, "Starting preprocessing");
 
     // generating 2d vector layout of source code
     let mut char_map: Vec<Vec<char>> = Vec::new();
@@ -29,28 +39,35 @@ pub fn process<'a>(src: &str) -> Result<FSProgram, Error> {
         char_map.push(row);
     }
     if n_chars == 0 {
-        return Err(Error::new(SyntaxError, SourcePos::zero(), "Empty file"));
+        return Err(vec![Error::new(
+            SyntaxError,
+            SourcePos::zero(),
+            "Empty file",
+        )]);
     }
 
     // finding all stations
-    debug!(2, "Parsing stations");
-    let (stations, mut functions) = station_parser::parse_stations(&char_map)?;
-    debug!(3, "Found {} stations", stations.len());
+    debug!(options, 2, "Parsing stations");
+    let mut errors: Vec<Error> = Vec::new();
+    let (stations, mut functions) =
+        station_parser::parse_stations(&char_map, registry, options, &mut errors);
+    debug!(options, 3, "Found {} stations", stations.len());
 
     // parsing connections between stations
-    debug!(2, "Parsing connections");
-    connection_parser::parse(&char_map, stations, &mut functions)?;
-    debug!(3, "Found {} functions", functions.len());
+    debug!(options, 2, "Parsing connections");
+    let unreachable =
+        connection_parser::parse(&char_map, stations, &mut functions, options, &mut errors);
+    debug!(options, 3, "Found {} functions", functions.len());
 
     // validating functions
-    debug!(2, "Validating functions");
+    debug!(options, 2, "Validating functions");
     for (i, f) in functions.iter_mut().enumerate() {
         let mut args_seen: HashSet<usize> = HashSet::new();
         for s in f.stations.iter() {
             if let StationData::FunctionIDAndIndex(_, arg_i) = s.data {
                 // station is an function input station
                 if args_seen.contains(&arg_i) {
-                    return Err(Error::new(
+                    errors.push(Error::new(
                         SyntaxError,
                         s.loc,
                         "Duplicate function arguments",
@@ -62,14 +79,31 @@ pub fn process<'a>(src: &str) -> Result<FSProgram, Error> {
                 f.n_args = cmp::max(f.n_args, arg_i + 1);
             }
         }
-        debug!(3, "function {i} '{}': {} args", f.name, f.n_args)
+        debug!(options, 3, "function {i} '{}': {} args", f.name, f.n_args)
     }
+    let mut warnings = analysis::check_functions(&functions, &mut errors);
 
-    debug!(2, "Finished preprocessing");
+    if !errors.is_empty() {
+        debug!(
+            options,
+            2,
+            "Preprocessing failed with {} errors",
+            errors.len()
+        );
+        return Err(errors);
+    }
+
+    // looking for likely mistakes
+    debug!(options, 2, "Checking stations");
+    warnings.extend(analysis::check_stations(&functions, &unreachable));
+    debug!(options, 3, "Found {} warnings", warnings.len());
+
+    debug!(options, 2, "Finished preprocessing");
 
     Ok(FSProgram {
         main: functions[0].clone(),
+        warnings,
         function_templates: functions,
-        benchmark: false,
+        preprocess_time: Duration::ZERO,
     })
 }
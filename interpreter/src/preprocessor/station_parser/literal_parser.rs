@@ -1,5 +1,8 @@
-use crate::*;
+use std::collections::BTreeMap;
 
+use crate::{pallet::MapKey, *};
+
+#[allow(clippy::ptr_arg)]
 pub fn parse_assign_literal(s: &String) -> Result<Pallet, String> {
     match s.as_str() {
         "" => {
@@ -36,6 +39,12 @@ pub fn parse_assign_literal(s: &String) -> Result<Pallet, String> {
             return Err("Malformed character literal".to_string());
         }
         return Ok(Pallet::Char(chars[1]));
+    } else if s.starts_with('[') {
+        // list literal
+        if !s.ends_with(']') {
+            return Err("Unclosed list literal".to_string());
+        }
+        return parse_list_literal(&s[1..(s.len() - 1)]);
     }
 
     let mut parsed_string = String::new();
@@ -73,7 +82,7 @@ pub fn parse_assign_literal(s: &String) -> Result<Pallet, String> {
 
     if !decimal && !float_terminal {
         // integer literal
-        match i64::from_str_radix(parsed_string.as_str(), 10) {
+        match parsed_string.parse::<i64>() {
             Ok(num) => return Ok(Pallet::Int(num)),
             Err(e) => {
                 return Err(format!("Failed to parse integer literal ({e})"));
@@ -89,3 +98,65 @@ pub fn parse_assign_literal(s: &String) -> Result<Pallet, String> {
         };
     }
 }
+
+/// Parses the comma separated elements of a list literal, which may be nested, or
+/// the `key: value` entries of a map literal (`:` alone for an empty map)
+fn parse_list_literal(s: &str) -> Result<Pallet, String> {
+    if s.trim().is_empty() {
+        return Ok(Pallet::List(Vec::new()));
+    }
+    if s.trim() == ":" {
+        return Ok(Pallet::Map(BTreeMap::new()));
+    }
+
+    let mut list: Vec<Pallet> = Vec::new();
+    let mut map: BTreeMap<MapKey, Pallet> = BTreeMap::new();
+    for element in split_top_level(s, ',')? {
+        let parts = split_top_level(&element, ':')?;
+        if parts.iter().any(|p| p.trim().is_empty()) {
+            return Err("Missing element in list literal".to_string());
+        }
+        match parts.as_slice() {
+            [value] => list.push(parse_assign_literal(&value.trim().to_string())?),
+            [key, value] => {
+                let key = parse_assign_literal(&key.trim().to_string())?;
+                let value = parse_assign_literal(&value.trim().to_string())?;
+                map.insert(MapKey::try_from(key)?, value);
+            }
+            _ => return Err("Malformed map literal entry".to_string()),
+        }
+    }
+    if !list.is_empty() && !map.is_empty() {
+        return Err("Literal mixes list elements and map entries".to_string());
+    }
+    if !map.is_empty() {
+        return Ok(Pallet::Map(map));
+    }
+    return Ok(Pallet::List(list));
+}
+
+/// Splits a string on a separator that isn't in nested lists or quotes
+fn split_top_level(s: &str, separator: char) -> Result<Vec<String>, String> {
+    let mut parts: Vec<String> = vec![String::new()];
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for c in s.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[') => depth += 1,
+            (None, ']') => depth -= 1,
+            (None, c) if c == separator && depth == 0 => {
+                parts.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        parts.last_mut().unwrap().push(c);
+    }
+    if quote.is_some() || depth != 0 {
+        return Err("Malformed list literal".to_string());
+    }
+    return Ok(parts);
+}
@@ -1,6 +1,10 @@
 mod literal_parser;
 
-use crate::{station::*, util::*, *};
+use crate::{
+    station::{registry::Registry, *},
+    util::*,
+    *,
+};
 use literal_parser::parse_assign_literal;
 
 enum State {
@@ -10,10 +14,14 @@ enum State {
     FunctionName,
     FunctionSuffix(usize),
     AssignStation,
+    /// Skipping the rest of a station that failed to parse, up to its closing
+    /// bracket
+    Invalid(char),
 }
 
 /// function to increment the position in the character map and get the next character
 /// there, if there is one
+#[allow(clippy::ptr_arg)]
 fn get_next_char(pos: &mut SourcePos, char_map: &Vec<Vec<char>>) -> Option<char> {
     pos.col += 1;
     while pos.col >= char_map[pos.line].len() {
@@ -27,11 +35,17 @@ fn get_next_char(pos: &mut SourcePos, char_map: &Vec<Vec<char>>) -> Option<char>
     return Some(c);
 }
 
-/// Identifies stations using a finite state machine. Returns a vector of stations
-/// discovered, and the assign table
+/// Identifies stations using a finite state machine, looking up station types in
+/// the registry. Returns a vector of stations discovered, and the assign table.
+///
+/// Errors are pushed to `errors` and parsing continues after them. A station that
+/// fails to parse is replaced with a joint, so that its belts can still be followed
 pub fn parse_stations(
     char_map: &Vec<Vec<char>>,
-) -> Result<(Vec<Station>, Vec<FunctionTemplate>), Error> {
+    registry: &Registry,
+    options: &RunOptions,
+    errors: &mut Vec<Error>,
+) -> (Vec<Station>, Vec<FunctionTemplate>) {
     let mut stations: Vec<Station> = Vec::new();
     let mut functions: Vec<FunctionTemplate> = Vec::new();
 
@@ -39,7 +53,7 @@ pub fn parse_stations(
     functions.push(FunctionTemplate::new("main".to_string()));
 
     let mut push_station = |s: Station| {
-        debug!(4, " - {s}");
+        debug!(options, 4, " - {s}");
         stations.push(s);
     };
 
@@ -47,11 +61,12 @@ pub fn parse_stations(
     // getting first character
     let mut c: char = loop {
         if pos.line >= char_map.len() {
-            return Err(Error::new(
+            errors.push(Error::new(
                 SyntaxError,
                 SourcePos::zero(),
                 "Empty factory program",
             ));
+            return (stations, functions);
         }
         if char_map[pos.line].len() > 0 {
             break char_map[pos.line][0];
@@ -66,7 +81,7 @@ pub fn parse_stations(
     let mut cur_token = String::new();
     let mut cur_station_pos = SourcePos::zero();
 
-    debug!(4, "Stations:");
+    debug!(options, 4, "Stations:");
     loop {
         // incrementing the state machine
         match state {
@@ -75,17 +90,17 @@ pub fn parse_stations(
                 '[' => {
                     state = State::Station;
                     cur_token = String::new();
-                    cur_station_pos = pos.clone();
+                    cur_station_pos = pos;
                 }
                 // start of assign station
                 '{' => {
                     state = State::AssignStation;
                     cur_token = String::new();
-                    cur_station_pos = pos.clone();
+                    cur_station_pos = pos;
                 }
                 // ehhh???
                 ']' | '}' => {
-                    return Err(Error::new(SyntaxError, pos, "Unexpected closing bracket"));
+                    errors.push(Error::new(SyntaxError, pos, "Unexpected closing bracket"));
                 }
                 // non station stuff (conveyor belts, floating comments)
                 _ => {}
@@ -93,22 +108,28 @@ pub fn parse_stations(
             State::Station => {
                 if c == ']' {
                     // new station w no modifiers
-                    push_station(Station::from_str(
-                        cur_token.as_str(),
-                        SourceSpan::new(cur_station_pos, cur_token.len() + 2),
-                    )?);
+                    let loc = SourceSpan::new(cur_station_pos, cur_token.len() + 2);
+                    match Station::from_str(cur_token.as_str(), loc, registry) {
+                        Ok(station) => push_station(station),
+                        Err(e) => {
+                            errors.push(e);
+                            push_station(Station::new(loc, &station::types::JOINT));
+                        }
+                    }
                     state = State::Default;
                 } else if c == '$' {
                     // function related station
                     if !cur_token.is_empty() {
                         // dollar sign in middle of station syntax
-                        return Err(Error::new(
+                        errors.push(Error::new(
                             SyntaxError,
                             pos,
                             "Invalid '$' character, must be at beginning of station declaration",
                         ));
+                        state = State::Invalid(']');
+                    } else {
+                        state = State::FunctionName;
                     }
-                    state = State::FunctionName;
                 } else if c == ':' {
                     // start of modifiers
                     state = State::StationModifiers(StationModifiers::default());
@@ -117,18 +138,19 @@ pub fn parse_stations(
                     cur_token.push(c);
                 } else {
                     // invalid character
-                    return Err(Error::new(
+                    errors.push(Error::new(
                         SyntaxError,
                         pos,
                         "Invalid character, station identifiers can only contain non-whitespace, printable ASCII characters",
                     ));
+                    state = State::Invalid(']');
                 }
             }
             State::FunctionName => {
                 if c == '.' || c == ']' {
                     // done reading function name, getting id
                     if cur_token == "main" {
-                        return Err(Error::new(
+                        errors.push(Error::new(
                             SyntaxError,
                             SourceSpan::new(cur_station_pos, cur_token.len() + 2),
                             "'main' is a reserved function name",
@@ -166,11 +188,12 @@ pub fn parse_stations(
                     cur_token.push(c);
                 } else {
                     // invalid character
-                    return Err(Error::new(
+                    errors.push(Error::new(
                         SyntaxError,
                         pos,
                         "Invalid character, function names can only contain non-whitespace, printable ASCII characters",
                     ));
+                    state = State::Invalid(']');
                 }
             }
             State::FunctionSuffix(id) => {
@@ -187,17 +210,19 @@ pub fn parse_stations(
                                 .with_data(StationData::FunctionIDAndIndex(id, index)),
                         );
                     } else {
-                        return Err(Error::new(
+                        errors.push(Error::new(
                             SyntaxError,
                             loc,
                             "Invalid function suffix, must be 'out' or a positive integer",
                         ));
+                        push_station(Station::new(loc, &station::types::JOINT));
                     }
                     state = State::Default;
                 } else if c.is_ascii_graphic() && !c.is_ascii_whitespace() {
                     cur_token.push(c);
                 } else {
-                    return Err(Error::new(SyntaxError, pos, "Invalid character"));
+                    errors.push(Error::new(SyntaxError, pos, "Invalid character"));
+                    state = State::Invalid(']');
                 }
             }
             State::StationModifiers(ref mods) => match c {
@@ -207,38 +232,40 @@ pub fn parse_stations(
                 'W' => state = State::StationModifiers(mods.with_priority(Direction::WEST)),
                 '*' => state = State::StationModifiers(mods.reverse()),
                 ']' => {
-                    push_station(
-                        Station::from_str(
-                            cur_token.as_str(),
-                            SourceSpan::new(cur_station_pos, pos.col - cur_station_pos.col),
-                        )?
-                        .with_modifiers(*mods),
-                    );
+                    let loc = SourceSpan::new(cur_station_pos, pos.col - cur_station_pos.col);
+                    match Station::from_str(cur_token.as_str(), loc, registry) {
+                        Ok(station) => push_station(station.with_modifiers(*mods)),
+                        Err(e) => {
+                            errors.push(e);
+                            push_station(Station::new(loc, &station::types::JOINT));
+                        }
+                    }
                     state = State::Default;
                 }
                 _ => {
                     // invalid character
-                    return Err(Error::new(
+                    errors.push(Error::new(
                         SyntaxError,
                         pos,
                         "Invalid modifier character, acceptable modifiers are 'N', 'S', 'E', 'W' and '~'",
                     ));
+                    state = State::Invalid(']');
                 }
             },
             State::AssignStation => match c {
                 '}' => {
                     // creating new station
                     let loc = SourceSpan::new(cur_station_pos, pos.col - cur_station_pos.col + 1);
-                    let assign_val = match parse_assign_literal(&cur_token) {
-                        Ok(p) => p,
+                    match parse_assign_literal(&cur_token) {
+                        Ok(assign_val) => push_station(
+                            Station::new(loc, &station::types::ASSIGN)
+                                .with_data(StationData::AssignValue(assign_val)),
+                        ),
                         Err(s) => {
-                            return Err(Error::new(SyntaxError, pos, s));
+                            errors.push(Error::new(SyntaxError, pos, s));
+                            push_station(Station::new(loc, &station::types::JOINT));
                         }
-                    };
-                    push_station(
-                        Station::new(loc, &station::types::ASSIGN)
-                            .with_data(StationData::AssignValue(assign_val.clone())),
-                    );
+                    }
                     state = State::Default;
                 }
                 '\\' => {
@@ -253,7 +280,8 @@ pub fn parse_stations(
                         Some('}') => '}',
                         Some(c) => c,
                         None => {
-                            return Err(Error::new(SyntaxError, pos, "Unexpected EOF"));
+                            errors.push(Error::new(SyntaxError, pos, "Unexpected EOF"));
+                            return (stations, functions);
                         }
                     });
                 }
@@ -261,6 +289,17 @@ pub fn parse_stations(
                     cur_token.push(c);
                 }
             },
+            State::Invalid(closing) => {
+                if c == closing {
+                    // keeping a placeholder if the station is on one line
+                    if pos.line == cur_station_pos.line {
+                        let loc =
+                            SourceSpan::new(cur_station_pos, pos.col - cur_station_pos.col + 1);
+                        push_station(Station::new(loc, &station::types::JOINT));
+                    }
+                    state = State::Default;
+                }
+            }
         }
 
         // getting next char
@@ -271,10 +310,8 @@ pub fn parse_stations(
             }
         };
     }
-    match state {
-        State::Default => {
-            return Ok((stations, functions));
-        }
-        _ => return Err(Error::new(SyntaxError, cur_station_pos, "Unexpected EOF")),
+    if !matches!(state, State::Default) {
+        errors.push(Error::new(SyntaxError, cur_station_pos, "Unexpected EOF"));
     }
+    return (stations, functions);
 }
@@ -0,0 +1,244 @@
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+use crate::{
+    runtime::StepLog,
+    station::{types, StationData},
+    util::SourceSpan,
+    FSProgram,
+};
+
+/// How often a station triggered during an execution
+#[derive(Debug, Clone)]
+pub struct StationProfile {
+    /// Identifier of the station's type
+    pub station: &'static str,
+    /// Location of the station in source code
+    pub loc: SourceSpan,
+    /// Name of the function the station is in
+    pub function: String,
+    /// Number of times the station triggered, in every invocation of its function
+    pub fires: u64,
+    /// Total time spent in the station's procedure
+    pub time: Duration,
+}
+
+/// How often a function was invoked during an execution
+#[derive(Debug, Clone)]
+pub struct FunctionProfile {
+    pub name: String,
+    pub invocations: u64,
+}
+
+/// Counts of station triggers and function invocations during an execution
+#[derive(Debug, Clone)]
+pub struct Profile {
+    /// Every station that is reachable, in source order for each function
+    pub stations: Vec<StationProfile>,
+    /// Every function, main first
+    pub functions: Vec<FunctionProfile>,
+}
+impl Profile {
+    /// Writes a table of the stations that triggered, most triggered first, and of
+    /// the number of invocations of each function
+    pub fn write_table(&self, out: &mut dyn Write) -> io::Result<()> {
+        let mut stations: Vec<&StationProfile> =
+            self.stations.iter().filter(|s| s.fires > 0).collect();
+        stations.sort_by(|a, b| b.fires.cmp(&a.fires).then(b.time.cmp(&a.time)));
+
+        writeln!(out, "\n=======Profile=======")?;
+        writeln!(
+            out,
+            " {:>10} {:>12}  {:<12} {:<14} function",
+            "triggers", "time", "station", "location"
+        )?;
+        for s in stations {
+            writeln!(
+                out,
+                " {:>10} {:>10.3}ms  {:<12} {:<14} {}",
+                s.fires,
+                s.time.as_secs_f64() * 1000.0,
+                s.station,
+                s.loc.to_string(),
+                s.function
+            )?;
+        }
+        writeln!(out)?;
+        writeln!(out, " {:>10}  function", "invocations")?;
+        for f in self.functions.iter() {
+            writeln!(out, " {:>10}  {}", f.invocations, f.name)?;
+        }
+        writeln!(out, "=====================")?;
+        return Ok(());
+    }
+
+    /// Writes a copy of the source with every station colored by how often it
+    /// triggered, from blue (least) to red (most). Stations that never triggered
+    /// are gray. Without color, the number of times each station triggered is
+    /// written below its first character
+    pub fn write_heatmap(&self, src: &str, color: bool, out: &mut dyn Write) -> io::Result<()> {
+        let max = self.stations.iter().map(|s| s.fires).max().unwrap_or(0);
+        for (line_i, line) in src.split('\n').enumerate() {
+            let mut stations: Vec<&StationProfile> = self
+                .stations
+                .iter()
+                .filter(|s| s.loc.pos.line == line_i)
+                .collect();
+            stations.sort_by_key(|s| s.loc.pos.col);
+
+            if color {
+                let spans = stations
+                    .iter()
+                    .map(|s| (s.loc, heat_color(s.fires, max)))
+                    .collect();
+                write_colored_line(out, line, spans)?;
+                writeln!(out)?;
+                continue;
+            }
+            writeln!(out, "{line}")?;
+            let mut counts = String::new();
+            for s in stations.iter() {
+                // counts too wide for their station push the next ones right
+                let len = counts.chars().count();
+                let col = if len == 0 {
+                    s.loc.pos.col
+                } else {
+                    s.loc.pos.col.max(len + 1)
+                };
+                counts.push_str(&" ".repeat(col - len));
+                counts.push_str(&s.fires.to_string());
+            }
+            if !counts.is_empty() {
+                writeln!(out, "{counts}")?;
+            }
+        }
+        // legend
+        if !color {
+            writeln!(out, "(trigger counts below each station)")?;
+            return Ok(());
+        }
+        write!(out, "{}never\x1b[0m  least ", heat_color(0, max))?;
+        for color in HEAT_COLORS {
+            write!(out, "{color}■")?;
+        }
+        writeln!(out, "\x1b[0m most triggered")?;
+        return Ok(());
+    }
+}
+
+/// Writes a line of source code with the stations on it colored, given the
+/// location and color of each of them
+pub(crate) fn write_colored_line(
+    out: &mut dyn Write,
+    line: &str,
+    mut spans: Vec<(SourceSpan, &str)>,
+) -> io::Result<()> {
+    spans.sort_by_key(|(loc, _)| loc.pos.col);
+    let mut spans = spans.into_iter().peekable();
+    let mut end: Option<usize> = None;
+    for (col, c) in line.chars().enumerate() {
+        if let Some((loc, color)) = spans.next_if(|(loc, _)| loc.pos.col == col) {
+            write!(out, "{color}")?;
+            end = Some(col + loc.len);
+        }
+        write!(out, "{c}")?;
+        if end == Some(col + 1) {
+            write!(out, "\x1b[0m")?;
+            end = None;
+        }
+    }
+    if end.is_some() {
+        write!(out, "\x1b[0m")?;
+    }
+    return Ok(());
+}
+
+/// Colors from least to most triggered
+const HEAT_COLORS: [&str; 5] = ["\x1b[34m", "\x1b[36m", "\x1b[32m", "\x1b[33m", "\x1b[31m"];
+
+/// Gets the color of a station in the heatmap, on a logarithmic scale
+fn heat_color(fires: u64, max: u64) -> &'static str {
+    if fires == 0 {
+        return "\x1b[90m";
+    }
+    if max <= 1 {
+        return HEAT_COLORS[HEAT_COLORS.len() - 1];
+    }
+    let scale = (fires as f64).ln() / (max as f64).ln();
+    let level = (scale * (HEAT_COLORS.len() - 1) as f64).round() as usize;
+    return HEAT_COLORS[level.min(HEAT_COLORS.len() - 1)];
+}
+
+/// Builds a profile from the log of every step
+pub(crate) struct Profiler<'a> {
+    program: &'a FSProgram,
+    /// Trigger count and procedure time of every station, indexed by function and
+    /// station index
+    stations: Vec<Vec<(u64, Duration)>>,
+    /// Invocation count of every function
+    invocations: Vec<u64>,
+}
+impl<'a> Profiler<'a> {
+    pub fn new(program: &'a FSProgram) -> Self {
+        let mut invocations = vec![0; program.function_templates.len()];
+        // main is invoked once
+        invocations[0] = 1;
+        return Profiler {
+            program,
+            stations: program
+                .function_templates
+                .iter()
+                .map(|f| vec![(0, Duration::ZERO); f.stations.len()])
+                .collect(),
+            invocations,
+        };
+    }
+
+    /// Counts the stations triggered during a step
+    pub fn record(&mut self, log: &StepLog) {
+        for firing in log.fired.iter() {
+            let function = self.program.template_index(firing.function);
+            let (fires, time) = &mut self.stations[function][firing.station];
+            *fires += 1;
+            *time += firing.time;
+
+            let station = firing.station();
+            if let StationData::FunctionID(id) = station.data {
+                if station.s_type == &types::FUNC_INVOKE {
+                    self.invocations[id] += 1;
+                }
+            }
+        }
+    }
+
+    pub fn finish(self) -> Profile {
+        let mut stations: Vec<StationProfile> = Vec::new();
+        for (f, counts) in self.program.function_templates.iter().zip(self.stations) {
+            for (s, (fires, time)) in f.stations.iter().zip(counts) {
+                stations.push(StationProfile {
+                    station: s.s_type.id,
+                    loc: s.loc,
+                    function: f.name.clone(),
+                    fires,
+                    time,
+                });
+            }
+        }
+        let functions = self
+            .program
+            .function_templates
+            .iter()
+            .zip(self.invocations)
+            .map(|(f, invocations)| FunctionProfile {
+                name: f.name.clone(),
+                invocations,
+            })
+            .collect();
+        return Profile {
+            stations,
+            functions,
+        };
+    }
+}
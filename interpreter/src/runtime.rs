@@ -1,29 +1,589 @@
-use crate::{debug, function::*, *};
+use std::{
+    fmt::Write,
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
 
-/// Spawns pallets from the start station and starts the execution loop, returns
-/// the number of steps in the program
-pub fn execute<'a>(program: &'a FSProgram) -> (Result<(), Error>, u64) {
-    debug!(2, "Starting execution");
-    let mut step_count: u64 = 0;
+use crate::{
+    coverage::{Coverage, CoverageRecorder},
+    debug,
+    function::*,
+    profile::{Profile, Profiler},
+    station::{types::Context, Station, StationData},
+    util::SourceSpan,
+    *,
+};
+
+/// Execution state of a program, can be advanced one step at a time
+pub struct Runtime<'a> {
+    /// Call frames of every live function, indexed by frame ID. The main function,
+    /// "root" of the call tree, is always frame 0
+    pub frames: Vec<Option<Function<'a>>>,
+    /// IDs of frames that are no longer in use
+    free_frames: Vec<usize>,
+    /// Maximum depth of function invocations, unlimited if `None`
+    pub max_depth: Option<u32>,
+    /// Record of what happened during the last step
+    pub log: StepLog<'a>,
+    /// Number of steps completed
+    pub step_count: u64,
+    /// Exit status given to the exit station, if one has been triggered
+    pub exit_status: Option<u8>,
+    /// Whether to record work left unfinished by functions in `stalls`
+    pub check_stalls: bool,
+    /// Work left unfinished by functions that are done executing
+    pub stalls: Vec<Stall<'a>>,
+}
+impl<'a> Runtime<'a> {
+    /// Spawns pallets from the start station
+    pub fn new(program: &'a FSProgram) -> Self {
+        Runtime {
+            frames: vec![Some(Function::instantiate_main(program))],
+            free_frames: Vec::new(),
+            max_depth: None,
+            log: StepLog::default(),
+            step_count: 0,
+            exit_status: None,
+            check_stalls: false,
+            stalls: Vec::new(),
+        }
+    }
+
+    /// Performs one time step (returns true if an exit station was triggered)
+    ///
+    /// Functions are stepped depth first, each function stepping its own stations,
+    /// then each of its children in the order they were invoked, collecting a
+    /// child's output after it is stepped
+    pub fn step(&mut self, ctx: &mut Context) -> Result<bool, Error> {
+        self.log.clear();
+
+        // stack of frames being stepped, with the index of the next child to step
+        let mut stack: Vec<(usize, usize)> = Vec::new();
+        if let Some(status) = self.step_frame(0, ctx)? {
+            return self.exit(status);
+        }
+        stack.push((0, 0));
 
-    // main function, "root" of the call tree
-    let mut main: Function<'a> = Function::instantiate_main(&program);
+        while let Some((id, next_child)) = stack.last_mut() {
+            let frame = self.frame(*id);
+            if *next_child < frame.children.len() {
+                let child = frame.children[*next_child];
+                *next_child += 1;
+                match self.step_frame(child, ctx) {
+                    Ok(Some(status)) => return self.exit(status),
+                    Ok(None) => {}
+                    Err(e) => return Err(e.with_backtrace(self.backtrace(&stack, child))),
+                }
+                stack.push((child, 0));
+                continue;
+            }
+
+            // done stepping this frame's children
+            let id = *id;
+            stack.pop();
+            self.retire_children(id);
+            if let Some((parent, _)) = stack.last() {
+                // spawning the output in the parent
+                let frame = self.frame(id);
+                if let Some(output) = frame.output.clone() {
+                    let parent_station = frame.parent_station;
+                    let parent = self.frame_mut(*parent);
+                    for dest in parent.stations[parent_station].out_bays.iter() {
+                        parent.moving_pallets.push((output.clone(), *dest));
+                    }
+                    let function = parent.template;
+                    if self.log.emits {
+                        self.log.emitted.push(Emit {
+                            function,
+                            station: parent_station,
+                        });
+                    }
+                }
+            }
+        }
+
+        self.step_count += 1;
+        return Ok(false);
+    }
+
+    /// Steps a frame's stations and gives frames to the functions it invoked,
+    /// returns the exit status if an exit station was triggered
+    fn step_frame(&mut self, id: usize, ctx: &mut Context) -> Result<Option<u8>, Error> {
+        let max_depth = self.max_depth;
+        let frame = self.frames[id].as_mut().unwrap();
+        if let Some(status) = frame.step(&mut self.log, max_depth, ctx)? {
+            return Ok(Some(status));
+        }
+        let spawned = std::mem::take(&mut frame.spawned);
+        for f in spawned {
+            let child = match self.free_frames.pop() {
+                Some(child) => {
+                    self.frames[child] = Some(f);
+                    child
+                }
+                None => {
+                    self.frames.push(Some(f));
+                    self.frames.len() - 1
+                }
+            };
+            self.frame_mut(id).children.push(child);
+        }
+        return Ok(None);
+    }
+
+    /// Frees the frames of a frame's children that are done executing
+    fn retire_children(&mut self, id: usize) {
+        let mut children = std::mem::take(&mut self.frame_mut(id).children);
+        children.retain(|child| {
+            if self.frame(*child).is_done() {
+                if self.check_stalls {
+                    self.record_stalls(*child, Some(id));
+                }
+                self.frames[*child] = None;
+                self.free_frames.push(*child);
+                return false;
+            }
+            return true;
+        });
+        self.frame_mut(id).children = children;
+    }
+
+    /// Records the work a frame that is done executing left unfinished: pallets
+    /// left in in bays, and the output it never produced for its invoking station
+    /// in the parent frame
+    fn record_stalls(&mut self, id: usize, parent: Option<usize>) {
+        let f = self.frame(id);
+        let mut found: Vec<Stall<'a>> = Vec::new();
+        for (i, s) in f.stations.iter().enumerate() {
+            if s.in_bays.is_empty() {
+                continue;
+            }
+            found.push(Stall {
+                kind: if f.fired[i] {
+                    StallKind::Stranded
+                } else {
+                    StallKind::Starved
+                },
+                function: f.template,
+                station: i,
+                pallets: s.in_bays.len(),
+                count: 1,
+            });
+        }
+        if let Some(parent) = parent {
+            let invoking = &self.frame(parent).stations[f.parent_station];
+            let has_output = f
+                .template
+                .stations
+                .iter()
+                .any(|s| s.s_type == &station::types::FUNC_OUTPUT);
+            if f.output.is_none() && has_output && !invoking.out_bays.is_empty() {
+                found.push(Stall {
+                    kind: StallKind::NoOutput,
+                    function: self.frame(parent).template,
+                    station: f.parent_station,
+                    pallets: 0,
+                    count: 1,
+                });
+            }
+        }
+
+        // the same stall in many invocations is recorded once
+        for stall in found {
+            match self
+                .stalls
+                .iter_mut()
+                .find(|other| other.same_place(&stall))
+            {
+                Some(other) => other.count += 1,
+                None => self.stalls.push(stall),
+            }
+        }
+    }
+
+    /// Lists the invocations that led to a frame, most recent first. `stack` holds
+    /// the frames from main down to the frame's parent
+    fn backtrace(&self, stack: &[(usize, usize)], id: usize) -> Vec<CallSite> {
+        let mut path: Vec<usize> = stack.iter().map(|(id, _)| *id).collect();
+        path.push(id);
+        let mut backtrace: Vec<CallSite> = Vec::new();
+        for pair in path.windows(2).rev() {
+            let (caller, callee) = (self.frame(pair[0]), self.frame(pair[1]));
+            backtrace.push(CallSite {
+                function: callee.template.name.clone(),
+                caller: caller.template.name.clone(),
+                loc: caller.stations[callee.parent_station].loc,
+            });
+        }
+        return backtrace;
+    }
+
+    fn exit(&mut self, status: u8) -> Result<bool, Error> {
+        self.exit_status = Some(status);
+        return Ok(true);
+    }
+
+    /// Gets a live frame
+    pub fn frame(&self, id: usize) -> &Function<'a> {
+        return self.frames[id].as_ref().expect("Frame is not live");
+    }
+
+    fn frame_mut(&mut self, id: usize) -> &mut Function<'a> {
+        return self.frames[id].as_mut().expect("Frame is not live");
+    }
+
+    /// Iterates over every live frame, in no particular order
+    pub fn live_frames(&self) -> impl Iterator<Item = &Function<'a>> {
+        return self.frames.iter().flatten();
+    }
+
+    /// Describes the state of every live function: its pallets moving on belts,
+    /// and its stations with partly filled in bays. Invoked functions are listed
+    /// under the function that invoked them
+    pub fn dump_state(&self) -> String {
+        let mut output = format!("State after {} steps:", self.step_count);
+        // stack of frames to describe, with their depth and parent frame
+        let mut stack: Vec<(usize, usize, usize)> = vec![(0, 0, 0)];
+        while let Some((id, depth, parent)) = stack.pop() {
+            let f = self.frame(id);
+            let indent = depth * 2;
+            if depth == 0 {
+                let _ = write!(output, "\n'{}'", f.template.name);
+            } else {
+                let parent = self.frame(parent);
+                let _ = write!(
+                    output,
+                    "\n{: <indent$}'{}' invoked @ {}",
+                    "", f.template.name, parent.stations[f.parent_station].loc
+                );
+            }
+
+            // pallets on belts
+            for (pallet, (dest, priority)) in f.moving_pallets.iter() {
+                let dest = &f.stations[*dest];
+                let _ = write!(
+                    output,
+                    "\n{: <indent$}  {pallet} moving to {} @ {} bay {priority}",
+                    "", dest.s_type, dest.loc
+                );
+            }
+
+            // stations waiting for more inputs
+            for (i, s) in f.stations.iter().enumerate() {
+                if s.in_bays.is_empty() {
+                    continue;
+                }
+                // bays that belts lead to
+                let mut priorities: Vec<u32> = f
+                    .stations
+                    .iter()
+                    .flat_map(|other| other.out_bays.iter())
+                    .filter(|(dest, _)| *dest == i)
+                    .map(|(_, priority)| *priority)
+                    .chain(s.in_bays.iter().map(|(priority, _)| *priority))
+                    .collect();
+                priorities.sort_unstable();
+                priorities.dedup();
+                let _ = write!(
+                    output,
+                    "\n{: <indent$}  {} @ {} waiting with",
+                    "", s.s_type, s.loc
+                );
+                for (j, priority) in priorities.iter().enumerate() {
+                    let sep = if j == 0 { "" } else { "," };
+                    match s.in_bays.iter().find(|(p, _)| p == priority) {
+                        Some((_, pallet)) => {
+                            let _ = write!(output, "{sep} bay {priority} filled ({pallet})");
+                        }
+                        None => {
+                            let _ = write!(output, "{sep} bay {priority} empty");
+                        }
+                    }
+                }
+            }
+
+            for child in f.children.iter().rev() {
+                stack.push((*child, depth + 1, id));
+            }
+        }
+        return output;
+    }
+
+    /// Returns whether or not the program is done executing
+    pub fn is_done(&self) -> bool {
+        return self.exit_status.is_some() || self.frame(0).is_done();
+    }
+}
+
+/// Record of everything that happened during a step
+#[derive(Default)]
+pub struct StepLog<'a> {
+    /// Whether to record pallets, moved pallets and station inputs/outputs are
+    /// only recorded if this is set
+    pub detailed: bool,
+    /// Whether to time station procedures
+    pub timed: bool,
+    /// Whether to record stations that put pallets on their belts
+    pub emits: bool,
+    /// Stations that were triggered
+    pub fired: Vec<Firing<'a>>,
+    /// Pallets that were moved into in bays
+    pub moved: Vec<Move<'a>>,
+    /// Stations that put a pallet on each of their outgoing belts (if emits)
+    pub emitted: Vec<Emit<'a>>,
+}
+impl<'a> StepLog<'a> {
+    /// Clears the log for the next step
+    pub fn clear(&mut self) {
+        self.fired.clear();
+        self.moved.clear();
+        self.emitted.clear();
+    }
+}
+
+/// Record of a station that was triggered during a step
+pub struct Firing<'a> {
+    /// Function the station belongs to
+    pub function: &'a FunctionTemplate,
+    /// Index of the station in the function
+    pub station: usize,
+    /// Pallets the station consumed (if detailed)
+    pub inputs: Vec<Pallet>,
+    /// Pallet the station produced (if detailed)
+    pub output: Option<Pallet>,
+    /// Time spent in the station's procedure (if timed)
+    pub time: Duration,
+}
+impl<'a> Firing<'a> {
+    /// Gets the triggered station, as it is defined in its function template
+    pub fn station(&self) -> &'a Station {
+        return &self.function.stations[self.station];
+    }
+}
+
+/// Record of a station that put a pallet on each of its outgoing belts during a
+/// step. Invoking stations do this when their function produces an output, and
+/// function input stations when their function is invoked
+pub struct Emit<'a> {
+    /// Function the station belongs to
+    pub function: &'a FunctionTemplate,
+    /// Index of the station in the function
+    pub station: usize,
+}
+
+/// Kinds of work a function can leave unfinished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StallKind {
+    /// Pallets left in the in bays of a station that has triggered before
+    Stranded,
+    /// Pallets left in the in bays of a station that never triggered
+    Starved,
+    /// An invoked function finished without producing an output for the invoking
+    /// station, whose output is used
+    NoOutput,
+}
+
+/// Record of work left unfinished by a function that is done executing
+pub struct Stall<'a> {
+    pub kind: StallKind,
+    /// Function the station belongs to
+    pub function: &'a FunctionTemplate,
+    /// Index of the station in the function, the invoking station for
+    /// [`StallKind::NoOutput`]
+    pub station: usize,
+    /// Number of pallets left in the station's in bays
+    pub pallets: usize,
+    /// Number of function instances this happened in
+    pub count: u64,
+}
+impl<'a> Stall<'a> {
+    /// Gets the station, as it is defined in its function template
+    pub fn station(&self) -> &'a Station {
+        return &self.function.stations[self.station];
+    }
+
+    /// Checks whether two stalls are the same kind and at the same station
+    fn same_place(&self, other: &Stall) -> bool {
+        return self.kind == other.kind
+            && std::ptr::eq(self.function, other.function)
+            && self.station == other.station;
+    }
+
+    /// Describes the stall as an error or a warning
+    pub fn to_error(&self, program: &FSProgram, t: ErrorType) -> Error {
+        let station = self.station();
+        let mut msg = match self.kind {
+            StallKind::Stranded => format!(
+                "{} pallet(s) left stranded in the in bays of station '{}' when '{}' finished",
+                self.pallets, station.s_type, self.function.name
+            ),
+            StallKind::Starved => format!(
+                "Station '{}' received {} of the {} input(s) it needs, but never triggered before '{}' finished",
+                station.s_type,
+                self.pallets,
+                station.inputs_needed(program),
+                self.function.name
+            ),
+            StallKind::NoOutput => {
+                let name = match station.data {
+                    StationData::FunctionID(id) => &program.function_templates[id].name,
+                    _ => &self.function.name,
+                };
+                format!("Function '{name}' finished without producing an output for this invocation")
+            }
+        };
+        if self.count > 1 {
+            msg += format!(" ({} times)", self.count).as_str();
+        }
+        return Error::new(t, station.loc, msg);
+    }
+}
+
+/// Record of a pallet that was moved into an in bay during a step
+pub struct Move<'a> {
+    /// Function the pallet was moved in
+    pub function: &'a FunctionTemplate,
+    /// The pallet
+    pub pallet: Pallet,
+    /// Destination station index and in bay priority
+    pub dest: (usize, u32),
+}
+
+/// How an execution that didn't stop with an error ended
+pub struct Outcome {
+    /// Exit status given to the exit station, if one was triggered
+    pub exit_status: Option<u8>,
+    /// Work left unfinished (see [`StallCheck`])
+    pub warnings: Vec<Error>,
+    /// Profile of the execution, if profiling
+    pub profile: Option<Profile>,
+    /// Coverage of the execution, if recording it
+    pub coverage: Option<Coverage>,
+}
+
+/// Spawns pallets from the start station and starts the execution loop, returns
+/// the number of steps in the program and how it ended.
+/// If the options have a trace output, a record of every step is written to it
+pub fn execute(program: &FSProgram, options: &mut RunOptions) -> (Result<Outcome, Error>, u64) {
+    debug!(options, 2, "Starting execution");
+    let start_time = Instant::now();
+    let mut runtime = Runtime::new(program);
+    runtime.max_depth = options.max_depth;
+    runtime.log.detailed = options.trace.is_some();
+    runtime.check_stalls = options.stall_check != StallCheck::Off;
+    runtime.log.timed = options.profile;
+    runtime.log.emits = options.coverage;
+    let mut profiler = options.profile.then(|| Profiler::new(program));
+    let mut recorder = options.coverage.then(|| CoverageRecorder::new(program));
+    let mut ctx = Context {
+        input: &mut options.input,
+        output: &mut options.output,
+        args: &options.args,
+        overflow: options.overflow,
+    };
 
     // execution loop
-    'execute_loop: while !main.is_done() {
-        match main.step() {
-            Ok(false) => {}
-            Ok(true) => {
-                break 'execute_loop;
+    let res: Result<(), Error> = loop {
+        if runtime.is_done() {
+            break Ok(());
+        }
+
+        // checking limits
+        if options
+            .max_steps
+            .is_some_and(|max| runtime.step_count >= max)
+        {
+            let msg = String::from("Step limit reached");
+            break Err(limit_error(&runtime, msg));
+        }
+        if let Some(timeout) = options.timeout {
+            if start_time.elapsed() >= timeout {
+                let msg = format!("Timeout of {:.3}s reached", timeout.as_secs_f64());
+                break Err(limit_error(&runtime, msg));
             }
-            Err(e) => {
-                return (Err(e), step_count);
+        }
+        if let Some(interrupt) = &options.interrupt {
+            if interrupt.load(Ordering::Relaxed) {
+                break Err(limit_error(&runtime, String::from("Interrupted")));
             }
+        }
+
+        let step = runtime.step_count;
+        if let Err(e) = runtime.step(&mut ctx) {
+            break Err(e);
+        }
+        if let Some(profiler) = profiler.as_mut() {
+            profiler.record(&runtime.log);
+        }
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.record(&runtime.log);
+        }
+        if let Some(out) = options.trace.as_mut() {
+            if let Err(e) = trace::write_step(out, step, &runtime.log) {
+                break Err(Error::new(
+                    RuntimeError,
+                    SourceSpan::zero(),
+                    format!("Failed to write trace: {e}"),
+                ));
+            }
+        }
+    };
+    if let Some(out) = options.trace.as_mut() {
+        let _ = out.flush();
+    }
+    if let Err(e) = res {
+        let mut e = if options.dump_state {
+            e.with_state(runtime.dump_state())
+        } else {
+            e
         };
+        if let Some(recorder) = recorder {
+            e = e.with_coverage(recorder.finish());
+        }
+        return (Err(e), runtime.step_count);
+    }
+    debug!(options, 2, "Execution completed");
 
-        step_count += 1;
+    // checking for unfinished work, if the program wasn't ended early
+    let mut warnings: Vec<Error> = Vec::new();
+    if runtime.check_stalls && runtime.exit_status.is_none() {
+        runtime.record_stalls(0, None);
+        if options.stall_check == StallCheck::Error && !runtime.stalls.is_empty() {
+            let mut e = runtime.stalls[0].to_error(program, RuntimeError);
+            for stall in runtime.stalls[1..].iter() {
+                let other = stall.to_error(program, RuntimeError);
+                e.msg += format!("\n  also @ {}: {}", other.loc, other.msg).as_str();
+            }
+            if let Some(recorder) = recorder {
+                e = e.with_coverage(recorder.finish());
+            }
+            return (Err(e), runtime.step_count);
+        }
+        for stall in runtime.stalls.iter() {
+            warnings.push(stall.to_error(program, Warning));
+        }
     }
-    debug!(2, "Execution completed");
 
-    return (Ok(()), step_count);
+    let outcome = Outcome {
+        exit_status: runtime.exit_status,
+        warnings,
+        profile: profiler.map(|p| p.finish()),
+        coverage: recorder.map(|r| r.finish()),
+    };
+    return (Ok(outcome), runtime.step_count);
+}
+
+/// Generates the error for when execution is stopped by a limit, describing what
+/// was still in progress
+fn limit_error(runtime: &Runtime, msg: String) -> Error {
+    let pallets: usize = runtime.live_frames().map(|f| f.moving_pallets.len()).sum();
+    let functions = runtime.live_frames().count() - 1;
+    return Error::new(
+        LimitError,
+        SourceSpan::zero(),
+        format!(
+            "{msg} after {} steps, {pallets} pallet(s) still moving and {functions} function invocation(s) still running",
+            runtime.step_count
+        ),
+    );
 }
@@ -1,4 +1,5 @@
 pub mod modifiers;
+pub mod registry;
 pub mod types;
 
 use std::fmt::Display;
@@ -6,7 +7,8 @@ use std::fmt::Display;
 pub use modifiers::StationModifiers;
 
 use crate::{util::*, Pallet, *};
-use types::{StationType, STATION_TYPES};
+use registry::Registry;
+use types::{StationType, StationTypeRef};
 
 /// Instance of a station
 #[derive(Debug, Clone)]
@@ -14,7 +16,7 @@ pub struct Station {
     /// Location of the station in source code
     pub loc: SourceSpan,
     /// Station type information
-    pub s_type: &'static StationType,
+    pub s_type: StationTypeRef,
     /// Data the station may need
     pub data: StationData,
     /// Modifiers
@@ -28,7 +30,7 @@ impl Station {
     pub fn new(loc: SourceSpan, s_type: &'static StationType) -> Self {
         Station {
             loc,
-            s_type,
+            s_type: StationTypeRef::Builtin(s_type),
             data: StationData::None,
             modifiers: StationModifiers::default(),
             in_bays: Vec::new(),
@@ -44,18 +46,16 @@ impl Station {
         Station { modifiers, ..self }
     }
 
-    pub fn from_str(identifier: &str, loc: SourceSpan) -> Result<Self, Error> {
-        for station_type in STATION_TYPES.iter() {
-            if station_type.has_id(identifier) {
-                return Ok(Self {
-                    loc,
-                    s_type: *station_type,
-                    modifiers: StationModifiers::default(),
-                    in_bays: Vec::new(),
-                    out_bays: Vec::new(),
-                    data: StationData::None,
-                });
-            }
+    pub fn from_str(identifier: &str, loc: SourceSpan, registry: &Registry) -> Result<Self, Error> {
+        if let Some(station_type) = registry.find(identifier) {
+            return Ok(Self {
+                loc,
+                s_type: station_type,
+                modifiers: StationModifiers::default(),
+                in_bays: Vec::new(),
+                out_bays: Vec::new(),
+                data: StationData::None,
+            });
         }
         return Err(Error::new(
             IdentifierError,
@@ -66,7 +66,15 @@ impl Station {
 
     /// Checks whether this station is ready to be triggered (if the length of inputs is >= the number of inputs needed)
     pub fn ready(&self, program: &FSProgram) -> bool {
-        let len = self.in_bays.len();
+        if self.s_type == &types::MAIN || self.s_type == &types::FUNC_INPUT {
+            // these stations can't trigger
+            return false;
+        }
+        return self.in_bays.len() >= self.inputs_needed(program);
+    }
+
+    /// Gets the number of inputs this station needs to trigger
+    pub fn inputs_needed(&self, program: &FSProgram) -> usize {
         if self.s_type == &types::FUNC_INVOKE {
             // if its a function invocation, check the number of args the function needs
             let function_id = if let StationData::FunctionID(id) = self.data {
@@ -74,12 +82,9 @@ impl Station {
             } else {
                 panic!();
             };
-            return len >= program.function_templates[function_id].n_args;
-        } else if self.s_type == &types::MAIN || self.s_type == &types::FUNC_INPUT {
-            // these stations can't trigger
-            return false;
+            return program.function_templates[function_id].n_args;
         }
-        return len >= self.s_type.inputs;
+        return self.s_type.inputs;
     }
 
     /// collects all the input pallets into a vector and clears the input bays
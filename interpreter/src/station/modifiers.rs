@@ -10,6 +10,7 @@ pub struct StationModifiers {
 }
 impl StationModifiers {
     /// Default modifiers for a station
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> Self {
         Self {
             reverse: false,
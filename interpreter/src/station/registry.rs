@@ -0,0 +1,62 @@
+use crate::{util::SourceSpan, *};
+
+use std::sync::Arc;
+
+use super::types::{StationType, StationTypeRef, STATION_TYPES};
+
+/// Set of station types that can be used in a program. Starts with the built in
+/// station types, and can be extended with host-defined ones before a program is
+/// loaded
+#[derive(Clone)]
+pub struct Registry {
+    types: Vec<StationTypeRef>,
+}
+impl Registry {
+    /// Creates a registry with the built in station types
+    pub fn new() -> Self {
+        Registry {
+            types: STATION_TYPES
+                .iter()
+                .map(|t| StationTypeRef::Builtin(t))
+                .collect(),
+        }
+    }
+
+    /// Registers a new station type, failing if any of its identifiers are invalid
+    /// or already in use. The station type is dropped with the registry and the
+    /// last program loaded with it
+    pub fn register(&mut self, s_type: StationType) -> Result<(), Error> {
+        for id in std::iter::once(&s_type.id).chain(s_type.alt_ids.iter()) {
+            if id.is_empty()
+                || !id
+                    .chars()
+                    .all(|c| c.is_ascii_graphic() && !"[]{}:$".contains(c))
+            {
+                return Err(Error::new(
+                    IdentifierError,
+                    SourceSpan::zero(),
+                    format!("Invalid station identifier \"{id}\""),
+                ));
+            }
+            if self.find(id).is_some() {
+                return Err(Error::new(
+                    IdentifierError,
+                    SourceSpan::zero(),
+                    format!("Station identifier \"{id}\" is already in use"),
+                ));
+            }
+        }
+        self.types.push(StationTypeRef::Host(Arc::new(s_type)));
+        return Ok(());
+    }
+
+    /// Finds the station type with an identifier
+    pub fn find(&self, identifier: &str) -> Option<StationTypeRef> {
+        return self.types.iter().find(|t| t.has_id(identifier)).cloned();
+    }
+}
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,51 +2,51 @@ use super::*;
 
 pub static MAIN: StationType = StationType {
     id: "main",
-    alt_id: None,
+    alt_ids: &[],
     inputs: 0,
     output: true,
-    procedure: main_procedure,
+    procedure: Procedure::Builtin(main_procedure),
 };
-fn main_procedure(_: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn main_procedure(_: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
     return Ok(Some(Pallet::Empty));
 }
 
 pub static EXIT: StationType = StationType {
     id: "exit",
-    alt_id: None,
+    alt_ids: &[],
     inputs: 1,
     output: false,
-    procedure: none_procedure,
+    procedure: Procedure::Builtin(none_procedure),
 };
 
 pub static JOINT: StationType = StationType {
     id: "joint",
-    alt_id: Some(""),
+    alt_ids: &[""],
     inputs: 1,
     output: true,
-    procedure: joint_procedure,
+    procedure: Procedure::Builtin(joint_procedure),
 };
-fn joint_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn joint_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 1, "Invalid argument count");
     return Ok(Some(pallets[0].clone()));
 }
 
 pub static ASSIGN: StationType = StationType {
     id: "assign",
-    alt_id: None,
+    alt_ids: &[],
     inputs: 1,
     output: true,
-    procedure: none_procedure,
+    procedure: Procedure::Builtin(none_procedure),
 };
 
 pub static GATE: StationType = StationType {
     id: "gate",
-    alt_id: None,
+    alt_ids: &[],
     inputs: 2,
     output: true,
-    procedure: gate_procedure,
+    procedure: Procedure::Builtin(gate_procedure),
 };
-fn gate_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn gate_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 2, "Invalid argument count");
     match (&pallets[0], &pallets[1]) {
         (Pallet::Bool(b), pallet) | (pallet, Pallet::Bool(b)) => {
@@ -63,12 +63,12 @@ fn gate_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
 
 pub static FILTER: StationType = StationType {
     id: "filter",
-    alt_id: Some("X"),
+    alt_ids: &["X"],
     inputs: 1,
     output: true,
-    procedure: filter_procedure,
+    procedure: Procedure::Builtin(filter_procedure),
 };
-fn filter_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn filter_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 1, "Invalid argument count");
     match &pallets[0] {
         Pallet::Bool(false) => Ok(None),
@@ -0,0 +1,207 @@
+use super::*;
+
+pub static TO_STRING: StationType = StationType {
+    id: "to_string",
+    alt_ids: &["str"],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(to_string_procedure),
+};
+fn to_string_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    if let Pallet::String(_) = &pallets[0] {
+        return Ok(Some(pallets[0].clone()));
+    }
+    // formatted the same way it is printed
+    let mut buf: Vec<u8> = Vec::new();
+    if let Err(e) = io::write_pallet(&mut buf, &pallets[0], false) {
+        return Err(format!("Failed to format pallet: {e}"));
+    }
+    return Ok(Some(Pallet::String(
+        String::from_utf8_lossy(&buf).into_owned(),
+    )));
+}
+
+pub static TO_INT: StationType = StationType {
+    id: "to_int",
+    alt_ids: &["parse_int"],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(to_int_procedure),
+};
+fn to_int_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::Int(i) => return Ok(Some(Pallet::Int(*i))),
+        Pallet::Bool(b) => return Ok(Some(Pallet::Int(*b as i64))),
+        Pallet::Float(f) => {
+            // truncating towards zero
+            return math::float_to_int(ctx, f.trunc(), || format!("truncating {f}"));
+        }
+        Pallet::Char(c) => match c.to_digit(10) {
+            Some(d) => return Ok(Some(Pallet::Int(d as i64))),
+            None => {
+                return Err(format!(
+                    "Failed to parse '{c}' as an integer, it is not a digit"
+                ))
+            }
+        },
+        Pallet::String(s) => match s.trim().replace('_', "").parse::<i64>() {
+            Ok(i) => return Ok(Some(Pallet::Int(i))),
+            Err(e) => return Err(format!("Failed to parse \"{s}\" as an integer ({e})")),
+        },
+        _ => {
+            return Err(format!(
+                "Expected a scalar pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static TO_FLOAT: StationType = StationType {
+    id: "to_float",
+    alt_ids: &["parse_float"],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(to_float_procedure),
+};
+fn to_float_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::Float(f) => return Ok(Some(Pallet::Float(*f))),
+        Pallet::Int(i) => return Ok(Some(Pallet::Float(*i as f64))),
+        Pallet::Bool(b) => return Ok(Some(Pallet::Float(if *b { 1.0 } else { 0.0 }))),
+        Pallet::Char(c) => match c.to_digit(10) {
+            Some(d) => return Ok(Some(Pallet::Float(d as f64))),
+            None => {
+                return Err(format!(
+                    "Failed to parse '{c}' as a float, it is not a digit"
+                ))
+            }
+        },
+        Pallet::String(s) => match s.trim().replace('_', "").parse::<f64>() {
+            Ok(f) => return Ok(Some(Pallet::Float(f))),
+            Err(e) => return Err(format!("Failed to parse \"{s}\" as a float ({e})")),
+        },
+        _ => {
+            return Err(format!(
+                "Expected a scalar pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static TO_BOOL: StationType = StationType {
+    id: "to_bool",
+    alt_ids: &["parse_bool"],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(to_bool_procedure),
+};
+fn to_bool_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::Bool(b) => return Ok(Some(Pallet::Bool(*b))),
+        Pallet::Int(i) => return Ok(Some(Pallet::Bool(*i != 0))),
+        Pallet::Float(f) => return Ok(Some(Pallet::Bool(*f != 0.0))),
+        Pallet::String(s) => match s.trim() {
+            "true" => return Ok(Some(Pallet::Bool(true))),
+            "false" => return Ok(Some(Pallet::Bool(false))),
+            _ => {
+                return Err(format!(
+                    "Failed to parse \"{s}\" as a boolean, expected \"true\" or \"false\""
+                ))
+            }
+        },
+        _ => {
+            return Err(format!(
+                "Expected a boolean, number or string pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static TO_CHAR: StationType = StationType {
+    id: "to_char",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(to_char_procedure),
+};
+fn to_char_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::Char(c) => return Ok(Some(Pallet::Char(*c))),
+        Pallet::Int(i) if (0..=9).contains(i) => {
+            return Ok(Some(Pallet::Char(char::from_digit(*i as u32, 10).unwrap())));
+        }
+        Pallet::Int(i) => {
+            return Err(format!(
+                "Integer {i} is not a single digit, use [chr] to convert a character code"
+            ));
+        }
+        Pallet::String(s) => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => return Ok(Some(Pallet::Char(c))),
+                _ => {
+                    return Err(format!(
+                        "Failed to convert \"{s}\" to a character, it must be exactly one character long"
+                    ));
+                }
+            }
+        }
+        _ => {
+            return Err(format!(
+                "Expected a character, integer or string pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static CHR: StationType = StationType {
+    id: "chr",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(chr_procedure),
+};
+fn chr_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::Int(i) => match u32::try_from(*i).ok().and_then(char::from_u32) {
+            Some(c) => return Ok(Some(Pallet::Char(c))),
+            None => return Err(format!("{i} is not a valid unicode character code")),
+        },
+        _ => {
+            return Err(format!(
+                "Expected an integer pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static ORD: StationType = StationType {
+    id: "ord",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(ord_procedure),
+};
+fn ord_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::Char(c) => return Ok(Some(Pallet::Int(*c as i64))),
+        _ => {
+            return Err(format!(
+                "Expected a character pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
@@ -2,24 +2,24 @@ use super::*;
 
 pub static FUNC_INVOKE: StationType = StationType {
     id: "func_invoke",
-    alt_id: None,
+    alt_ids: &[],
     inputs: 0,
     output: true,
-    procedure: none_procedure,
+    procedure: Procedure::Builtin(none_procedure),
 };
 
 pub static FUNC_INPUT: StationType = StationType {
     id: "func_input",
-    alt_id: None,
+    alt_ids: &[],
     inputs: 0,
     output: true,
-    procedure: none_procedure,
+    procedure: Procedure::Builtin(none_procedure),
 };
 
 pub static FUNC_OUTPUT: StationType = StationType {
     id: "func_output",
-    alt_id: None,
+    alt_ids: &[],
     inputs: 1,
     output: true,
-    procedure: none_procedure,
+    procedure: Procedure::Builtin(none_procedure),
 };
@@ -1,65 +1,93 @@
-use std::io::{stdin, stdout, Write};
-
 use super::*;
 
 pub static PRINT: StationType = StationType {
     id: "print",
-    alt_id: None,
+    alt_ids: &[],
     inputs: 1,
     output: false,
-    procedure: print_procedure,
+    procedure: Procedure::Builtin(print_procedure),
 };
-fn print_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn print_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 1, "Invalid argument count");
-    match &pallets[0] {
-        Pallet::Empty => {}
+    if let Err(e) = write_pallet(ctx.output, &pallets[0], false) {
+        return Err(format!("Failed to write output: {e}"));
+    }
+    return Ok(None);
+}
+
+/// Writes a pallet's value, characters and strings in a list or map are quoted
+pub(super) fn write_pallet(
+    out: &mut dyn Write,
+    pallet: &Pallet,
+    quoted: bool,
+) -> std::io::Result<()> {
+    return match pallet {
+        Pallet::Empty => Ok(()),
         Pallet::Bool(b) => {
             if *b {
-                print!("true");
+                write!(out, "true")
             } else {
-                print!("false");
+                write!(out, "false")
             }
         }
-        Pallet::Char(c) => {
-            print!("{c}");
-        }
-        Pallet::String(s) => {
-            print!("{s}");
-        }
-        Pallet::Int(i) => {
-            print!("{i}");
+        Pallet::Char(c) if quoted => write!(out, "'{c}'"),
+        Pallet::Char(c) => write!(out, "{c}"),
+        Pallet::String(s) if quoted => write!(out, "\"{s}\""),
+        Pallet::String(s) => write!(out, "{s}"),
+        Pallet::Int(i) => write!(out, "{i}"),
+        Pallet::Float(f) => write!(out, "{f}"),
+        Pallet::List(l) => {
+            write!(out, "[")?;
+            for (i, p) in l.iter().enumerate() {
+                if i > 0 {
+                    write!(out, ", ")?;
+                }
+                write_pallet(out, p, true)?;
+            }
+            write!(out, "]")
         }
-        Pallet::Float(f) => {
-            print!("{f}");
+        Pallet::Map(m) if m.is_empty() => write!(out, "[:]"),
+        Pallet::Map(m) => {
+            write!(out, "[")?;
+            for (i, (k, v)) in m.iter().enumerate() {
+                if i > 0 {
+                    write!(out, ", ")?;
+                }
+                write_pallet(out, &Pallet::from(k.clone()), true)?;
+                write!(out, ": ")?;
+                write_pallet(out, v, true)?;
+            }
+            write!(out, "]")
         }
-    }
-    return Ok(None);
+    };
 }
 
 pub static PRINTLN: StationType = StationType {
     id: "println",
-    alt_id: None,
+    alt_ids: &[],
     inputs: 1,
     output: false,
-    procedure: println_procedure,
+    procedure: Procedure::Builtin(println_procedure),
 };
-fn println_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
-    print_procedure(pallets)?;
-    println!();
+fn println_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
+    print_procedure(pallets, ctx)?;
+    if let Err(e) = writeln!(ctx.output) {
+        return Err(format!("Failed to write output: {e}"));
+    }
     return Ok(None);
 }
 
 pub static READLN: StationType = StationType {
     id: "readln",
-    alt_id: None,
+    alt_ids: &[],
     inputs: 1,
     output: true,
-    procedure: readln_procedure,
+    procedure: Procedure::Builtin(readln_procedure),
 };
-fn readln_procedure(_: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn readln_procedure(_: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
     let mut input = String::new();
-    let _ = stdout().flush();
-    match stdin().read_line(&mut input) {
+    let _ = ctx.output.flush();
+    match ctx.input.read_line(&mut input) {
         Err(e) => return Err(e.to_string()),
         Ok(_) => {
             return Ok(Some(Pallet::String(if input.ends_with('\n') {
@@ -70,3 +98,42 @@ fn readln_procedure(_: Vec<Pallet>) -> Result<Option<Pallet>, String> {
         }
     }
 }
+
+pub static ARGC: StationType = StationType {
+    id: "argc",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(argc_procedure),
+};
+fn argc_procedure(_: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
+    return Ok(Some(Pallet::Int(ctx.args.len() as i64)));
+}
+
+pub static ARG: StationType = StationType {
+    id: "arg",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(arg_procedure),
+};
+fn arg_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::Int(i) => match usize::try_from(*i).ok().and_then(|i| ctx.args.get(i)) {
+            Some(arg) => return Ok(Some(Pallet::String(arg.clone()))),
+            None => {
+                return Err(format!(
+                    "Argument index {i} is out of range, {} argument(s) were given",
+                    ctx.args.len()
+                ));
+            }
+        },
+        _ => {
+            return Err(format!(
+                "Expected an integer pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use super::*;
+
+pub static PUSH: StationType = StationType {
+    id: "push",
+    alt_ids: &[],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(push_procedure),
+};
+fn push_procedure(mut pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::List(_) => {
+            let value = pallets.pop().unwrap();
+            let mut list = pallets.swap_remove(0);
+            if let Pallet::List(l) = &mut list {
+                l.push(value);
+            }
+            return Ok(Some(list));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a list pallet and a pallet to push onto it, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static POP: StationType = StationType {
+    id: "pop",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(pop_procedure),
+};
+fn pop_procedure(mut pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &mut pallets[0] {
+        Pallet::List(list) => {
+            if list.pop().is_none() {
+                return Err(String::from("Can't pop from an empty list"));
+            }
+            return Ok(Some(pallets.swap_remove(0)));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a list pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static GET: StationType = StationType {
+    id: "get",
+    alt_ids: &[],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(get_procedure),
+};
+fn get_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match (&pallets[0], &pallets[1]) {
+        (Pallet::List(list), Pallet::Int(i)) => {
+            let i = list_index(*i, list.len())?;
+            return Ok(Some(list[i].clone()));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a list pallet and an integer pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static SET: StationType = StationType {
+    id: "set",
+    alt_ids: &[],
+    inputs: 3,
+    output: true,
+    procedure: Procedure::Builtin(set_procedure),
+};
+fn set_procedure(mut pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 3, "Invalid argument count");
+    match (&pallets[0], &pallets[1]) {
+        (Pallet::List(list), Pallet::Int(i)) => {
+            let i = list_index(*i, list.len())?;
+            let value = pallets.pop().unwrap();
+            let mut list = pallets.swap_remove(0);
+            if let Pallet::List(l) = &mut list {
+                l[i] = value;
+            }
+            return Ok(Some(list));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a list pallet, an integer pallet and a pallet to set, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static LEN: StationType = StationType {
+    id: "len",
+    alt_ids: &["length"],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(len_procedure),
+};
+fn len_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::List(list) => {
+            return Ok(Some(Pallet::Int(list.len() as i64)));
+        }
+        Pallet::Map(map) => {
+            return Ok(Some(Pallet::Int(map.len() as i64)));
+        }
+        Pallet::String(s) => {
+            return Ok(Some(Pallet::Int(s.chars().count() as i64)));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a list, map or string pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static CONCAT: StationType = StationType {
+    id: "concat",
+    alt_ids: &[],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(concat_procedure),
+};
+fn concat_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match (&pallets[0], &pallets[1]) {
+        (Pallet::List(list1), Pallet::List(list2)) => {
+            let mut list = list1.clone();
+            list.extend_from_slice(list2);
+            return Ok(Some(Pallet::List(list)));
+        }
+        _ => {
+            return Err(format!(
+                "Expected two list pallets, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static SLICE: StationType = StationType {
+    id: "slice",
+    alt_ids: &[],
+    inputs: 3,
+    output: true,
+    procedure: Procedure::Builtin(slice_procedure),
+};
+fn slice_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 3, "Invalid argument count");
+    match (&pallets[0], &pallets[1], &pallets[2]) {
+        (Pallet::List(list), Pallet::Int(start), Pallet::Int(end)) => {
+            // the end is exclusive, so it may be the length of the list
+            match (usize::try_from(*start), usize::try_from(*end)) {
+                (Ok(start), Ok(end)) if start <= end && end <= list.len() => {
+                    return Ok(Some(Pallet::List(list[start..end].to_vec())));
+                }
+                _ => {
+                    return Err(format!(
+                        "Slice from {start} to {end} is out of bounds for a list of length {}",
+                        list.len()
+                    ));
+                }
+            }
+        }
+        _ => {
+            return Err(format!(
+                "Expected a list pallet and two integer pallets, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+/// Checks that an index is in bounds for a list of a given length
+fn list_index(i: i64, len: usize) -> Result<usize, String> {
+    return match usize::try_from(i) {
+        Ok(i) if i < len => Ok(i),
+        _ => Err(format!(
+            "Index {i} is out of bounds for a list of length {len}"
+        )),
+    };
+}
@@ -0,0 +1,151 @@
+use super::*;
+use crate::pallet::MapKey;
+
+pub static INSERT: StationType = StationType {
+    id: "insert",
+    alt_ids: &[],
+    inputs: 3,
+    output: true,
+    procedure: Procedure::Builtin(insert_procedure),
+};
+fn insert_procedure(mut pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 3, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::Map(_) => {
+            let value = pallets.pop().unwrap();
+            let key = MapKey::try_from(pallets.pop().unwrap())?;
+            let mut map = pallets.swap_remove(0);
+            if let Pallet::Map(m) = &mut map {
+                m.insert(key, value);
+            }
+            return Ok(Some(map));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a map pallet, a key pallet and a pallet to insert, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static LOOKUP: StationType = StationType {
+    id: "lookup",
+    alt_ids: &[],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(lookup_procedure),
+};
+fn lookup_procedure(mut pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    let key = pallets.pop().unwrap();
+    match pallets.swap_remove(0) {
+        Pallet::Map(mut m) => {
+            // missing keys have an empty pallet
+            let key = MapKey::try_from(key)?;
+            return Ok(Some(m.remove(&key).unwrap_or(Pallet::Empty)));
+        }
+        p => {
+            return Err(format!(
+                "Expected a map pallet and a key pallet, received: {}\n",
+                list_pallets(&vec![p, key])
+            ));
+        }
+    }
+}
+
+pub static REMOVE: StationType = StationType {
+    id: "remove",
+    alt_ids: &[],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(remove_procedure),
+};
+fn remove_procedure(mut pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::Map(_) => {
+            let key = MapKey::try_from(pallets.pop().unwrap())?;
+            let mut map = pallets.swap_remove(0);
+            if let Pallet::Map(m) = &mut map {
+                m.remove(&key);
+            }
+            return Ok(Some(map));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a map pallet and a key pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static HAS_KEY: StationType = StationType {
+    id: "has_key",
+    alt_ids: &[],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(has_key_procedure),
+};
+fn has_key_procedure(mut pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    let key = pallets.pop().unwrap();
+    match pallets.swap_remove(0) {
+        Pallet::Map(m) => {
+            let key = MapKey::try_from(key)?;
+            return Ok(Some(Pallet::Bool(m.contains_key(&key))));
+        }
+        p => {
+            return Err(format!(
+                "Expected a map pallet and a key pallet, received: {}\n",
+                list_pallets(&vec![p, key])
+            ));
+        }
+    }
+}
+
+pub static KEYS: StationType = StationType {
+    id: "keys",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(keys_procedure),
+};
+fn keys_procedure(mut pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match pallets.swap_remove(0) {
+        Pallet::Map(m) => {
+            let keys = m.into_keys().map(Pallet::from).collect();
+            return Ok(Some(Pallet::List(keys)));
+        }
+        p => {
+            return Err(format!(
+                "Expected a map pallet, received: {}\n",
+                list_pallets(&vec![p])
+            ));
+        }
+    }
+}
+
+pub static VALUES: StationType = StationType {
+    id: "values",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(values_procedure),
+};
+fn values_procedure(mut pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match pallets.swap_remove(0) {
+        Pallet::Map(m) => {
+            return Ok(Some(Pallet::List(m.into_values().collect())));
+        }
+        p => {
+            return Err(format!(
+                "Expected a map pallet, received: {}\n",
+                list_pallets(&vec![p])
+            ));
+        }
+    }
+}
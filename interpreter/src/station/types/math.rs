@@ -1,151 +1,112 @@
+use std::cmp::Ordering;
+
 use super::*;
 
 pub static EQUALS: StationType = StationType {
     id: "eq",
-    alt_id: Some("="),
+    alt_ids: &["="],
     inputs: 2,
     output: true,
-    procedure: equals_procedure,
+    procedure: Procedure::Builtin(equals_procedure),
 };
-fn equals_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
-    return Ok(Some(Pallet::Bool(pallets[0] == pallets[1])));
+fn equals_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    return Ok(Some(Pallet::Bool(equivalent(&pallets[0], &pallets[1]))));
 }
 
 pub static NOT_EQUALS: StationType = StationType {
     id: "ne",
-    alt_id: Some("!="),
+    alt_ids: &["!="],
     inputs: 2,
     output: true,
-    procedure: not_equals_procedure,
+    procedure: Procedure::Builtin(not_equals_procedure),
 };
-fn not_equals_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
-    return Ok(Some(Pallet::Bool(pallets[0] != pallets[1])));
+fn not_equals_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    return Ok(Some(Pallet::Bool(!equivalent(&pallets[0], &pallets[1]))));
 }
 
 pub static GREATER_THAN: StationType = StationType {
     id: "gt",
-    alt_id: Some(">"),
+    alt_ids: &[">"],
     inputs: 2,
     output: true,
-    procedure: greater_than_procedure,
+    procedure: Procedure::Builtin(greater_than_procedure),
 };
-fn greater_than_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn greater_than_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 2, "Invalid argument count");
-    match (&pallets[0], &pallets[1]) {
-        (Pallet::Int(num1), Pallet::Int(num2)) => {
-            return Ok(Some(Pallet::Bool(num1 > num2)));
-        }
-        (Pallet::Float(num1), Pallet::Float(num2)) => {
-            return Ok(Some(Pallet::Bool(num1 > num2)));
-        }
-        (Pallet::Bool(bool1), Pallet::Bool(bool2)) => {
-            return Ok(Some(Pallet::Bool(bool1 > bool2)));
-        }
-        _ => {
-            return Err(format!(
-                "Expected matching numerical or boolean pallets, received: {}\n",
-                list_pallets(&pallets)
-            ));
-        }
-    }
+    let ord = compare(&pallets)?;
+    return Ok(Some(Pallet::Bool(matches!(ord, Some(Ordering::Greater)))));
 }
 
 pub static LESS_THAN: StationType = StationType {
     id: "lt",
-    alt_id: Some("<"),
+    alt_ids: &["<"],
     inputs: 2,
     output: true,
-    procedure: less_than_procedure,
+    procedure: Procedure::Builtin(less_than_procedure),
 };
-fn less_than_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn less_than_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 2, "Invalid argument count");
-    match (&pallets[0], &pallets[1]) {
-        (Pallet::Int(num1), Pallet::Int(num2)) => {
-            return Ok(Some(Pallet::Bool(num1 < num2)));
-        }
-        (Pallet::Float(num1), Pallet::Float(num2)) => {
-            return Ok(Some(Pallet::Bool(num1 < num2)));
-        }
-        (Pallet::Bool(bool1), Pallet::Bool(bool2)) => {
-            return Ok(Some(Pallet::Bool(bool1 < bool2)));
-        }
-        _ => {
-            return Err(format!(
-                "Expected matching numerical or boolean pallets, received: {}\n",
-                list_pallets(&pallets)
-            ));
-        }
-    }
+    let ord = compare(&pallets)?;
+    return Ok(Some(Pallet::Bool(matches!(ord, Some(Ordering::Less)))));
 }
 
 pub static GREATER_THAN_EQUAL: StationType = StationType {
     id: "gte",
-    alt_id: Some(">="),
+    alt_ids: &[">="],
     inputs: 2,
     output: true,
-    procedure: greater_than_equal_procedure,
+    procedure: Procedure::Builtin(greater_than_equal_procedure),
 };
-fn greater_than_equal_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn greater_than_equal_procedure(
+    pallets: Vec<Pallet>,
+    _: &mut Context,
+) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 2, "Invalid argument count");
-    match (&pallets[0], &pallets[1]) {
-        (Pallet::Int(num1), Pallet::Int(num2)) => {
-            return Ok(Some(Pallet::Bool(num1 >= num2)));
-        }
-        (Pallet::Float(num1), Pallet::Float(num2)) => {
-            return Ok(Some(Pallet::Bool(num1 >= num2)));
-        }
-        (Pallet::Bool(bool1), Pallet::Bool(bool2)) => {
-            return Ok(Some(Pallet::Bool(bool1 >= bool2)));
-        }
-        _ => {
-            return Err(format!(
-                "Expected matching numerical or boolean pallets, received: {}\n",
-                list_pallets(&pallets)
-            ));
-        }
-    }
+    let ord = compare(&pallets)?;
+    return Ok(Some(Pallet::Bool(matches!(
+        ord,
+        Some(Ordering::Greater | Ordering::Equal)
+    ))));
 }
 
 pub static LESS_THAN_EQUAL: StationType = StationType {
     id: "lte",
-    alt_id: Some("<="),
+    alt_ids: &["<="],
     inputs: 2,
     output: true,
-    procedure: less_than_equal_procedure,
+    procedure: Procedure::Builtin(less_than_equal_procedure),
 };
-fn less_than_equal_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn less_than_equal_procedure(
+    pallets: Vec<Pallet>,
+    _: &mut Context,
+) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 2, "Invalid argument count");
-    match (&pallets[0], &pallets[1]) {
-        (Pallet::Int(num1), Pallet::Int(num2)) => {
-            return Ok(Some(Pallet::Bool(num1 <= num2)));
-        }
-        (Pallet::Float(num1), Pallet::Float(num2)) => {
-            return Ok(Some(Pallet::Bool(num1 <= num2)));
-        }
-        (Pallet::Bool(bool1), Pallet::Bool(bool2)) => {
-            return Ok(Some(Pallet::Bool(bool1 <= bool2)));
-        }
-        _ => {
-            return Err(format!(
-                "Expected matching numerical or boolean pallets, received: {}\n",
-                list_pallets(&pallets)
-            ));
-        }
-    }
+    let ord = compare(&pallets)?;
+    return Ok(Some(Pallet::Bool(matches!(
+        ord,
+        Some(Ordering::Less | Ordering::Equal)
+    ))));
 }
 
 pub static ADD: StationType = StationType {
     id: "add",
-    alt_id: Some("+"),
+    alt_ids: &["+"],
     inputs: 2,
     output: true,
-    procedure: add_procedure,
+    procedure: Procedure::Builtin(add_procedure),
 };
-fn add_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn add_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    let pallets = promote(pallets);
     match (&pallets[0], &pallets[1]) {
         (Pallet::Int(num1), Pallet::Int(num2)) => {
-            return Ok(Some(Pallet::Int(num1 + num2)));
+            return int_result(
+                ctx,
+                num1.checked_add(*num2),
+                || num1.wrapping_add(*num2),
+                || num1.saturating_add(*num2),
+                || format!("the sum of {num1} and {num2}"),
+            );
         }
         (Pallet::Float(num1), Pallet::Float(num2)) => {
             return Ok(Some(Pallet::Float(num1 + num2)));
@@ -169,16 +130,23 @@ fn add_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
 
 pub static SUBTRACT: StationType = StationType {
     id: "sub",
-    alt_id: Some("-"),
+    alt_ids: &["-"],
     inputs: 2,
     output: true,
-    procedure: subtract_procedure,
+    procedure: Procedure::Builtin(subtract_procedure),
 };
-fn subtract_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn subtract_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    let pallets = promote(pallets);
     match (&pallets[0], &pallets[1]) {
         (Pallet::Int(num1), Pallet::Int(num2)) => {
-            return Ok(Some(Pallet::Int(num1 - num2)));
+            return int_result(
+                ctx,
+                num1.checked_sub(*num2),
+                || num1.wrapping_sub(*num2),
+                || num1.saturating_sub(*num2),
+                || format!("the difference of {num1} and {num2}"),
+            );
         }
         (Pallet::Float(num1), Pallet::Float(num2)) => {
             return Ok(Some(Pallet::Float(num1 - num2)));
@@ -194,16 +162,23 @@ fn subtract_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
 
 pub static MULTIPLY: StationType = StationType {
     id: "mult",
-    alt_id: Some("*"),
+    alt_ids: &["*"],
     inputs: 2,
     output: true,
-    procedure: multiply_procedure,
+    procedure: Procedure::Builtin(multiply_procedure),
 };
-fn multiply_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn multiply_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    let pallets = promote(pallets);
     match (&pallets[0], &pallets[1]) {
         (Pallet::Int(num1), Pallet::Int(num2)) => {
-            return Ok(Some(Pallet::Int(num1 * num2)));
+            return int_result(
+                ctx,
+                num1.checked_mul(*num2),
+                || num1.wrapping_mul(*num2),
+                || num1.saturating_mul(*num2),
+                || format!("the product of {num1} and {num2}"),
+            );
         }
         (Pallet::Float(num1), Pallet::Float(num2)) => {
             return Ok(Some(Pallet::Float(num1 * num2)));
@@ -219,19 +194,27 @@ fn multiply_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
 
 pub static DIVIDE: StationType = StationType {
     id: "div",
-    alt_id: Some("/"),
+    alt_ids: &["/"],
     inputs: 2,
     output: true,
-    procedure: divide_procedure,
+    procedure: Procedure::Builtin(divide_procedure),
 };
-fn divide_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn divide_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    let pallets = promote(pallets);
     match (&pallets[0], &pallets[1]) {
         (Pallet::Int(num1), Pallet::Int(num2)) => {
             if *num2 == 0 {
                 return Err(String::from("Attempted divide by zero"));
             }
-            return Ok(Some(Pallet::Int(num1 / num2)));
+            // the smallest integer divided by -1 overflows
+            return int_result(
+                ctx,
+                num1.checked_div(*num2),
+                || num1.wrapping_div(*num2),
+                || num1.saturating_div(*num2),
+                || format!("the quotient of {num1} and {num2}"),
+            );
         }
         (Pallet::Float(num1), Pallet::Float(num2)) => {
             if *num2 == 0.0 {
@@ -250,19 +233,28 @@ fn divide_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
 
 pub static MODULO: StationType = StationType {
     id: "mod",
-    alt_id: Some("%"),
+    alt_ids: &["%"],
     inputs: 2,
     output: true,
-    procedure: modulo_procedure,
+    procedure: Procedure::Builtin(modulo_procedure),
 };
-fn modulo_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn modulo_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    let pallets = promote(pallets);
     match (&pallets[0], &pallets[1]) {
         (Pallet::Int(num1), Pallet::Int(num2)) => {
             if *num2 == 0 {
                 return Err(String::from("Attempted divide by zero"));
             }
-            return Ok(Some(Pallet::Int(num1 % num2)));
+            // the remainder of the smallest integer and -1 overflows, it's 0 when
+            // wrapping or saturating
+            return int_result(
+                ctx,
+                num1.checked_rem(*num2),
+                || num1.wrapping_rem(*num2),
+                || num1.wrapping_rem(*num2),
+                || format!("the remainder of {num1} and {num2}"),
+            );
         }
         (Pallet::Float(num1), Pallet::Float(num2)) => {
             if *num2 == 0.0 {
@@ -281,16 +273,22 @@ fn modulo_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
 
 pub static INCREMENT: StationType = StationType {
     id: "inc",
-    alt_id: Some("++"),
+    alt_ids: &["++"],
     inputs: 1,
     output: true,
-    procedure: increment_procedure,
+    procedure: Procedure::Builtin(increment_procedure),
 };
-fn increment_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn increment_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 1, "Invalid argument count");
     match &pallets[0] {
         Pallet::Int(num) => {
-            return Ok(Some(Pallet::Int(num + 1)));
+            return int_result(
+                ctx,
+                num.checked_add(1),
+                || num.wrapping_add(1),
+                || num.saturating_add(1),
+                || format!("incrementing {num}"),
+            );
         }
         Pallet::Float(num) => {
             return Ok(Some(Pallet::Float(num + 1.0)));
@@ -306,16 +304,22 @@ fn increment_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
 
 pub static DECREMENT: StationType = StationType {
     id: "dec",
-    alt_id: Some("--"),
+    alt_ids: &["--"],
     inputs: 1,
     output: true,
-    procedure: decrement_procedure,
+    procedure: Procedure::Builtin(decrement_procedure),
 };
-fn decrement_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn decrement_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 1, "Invalid argument count");
     match &pallets[0] {
         Pallet::Int(num) => {
-            return Ok(Some(Pallet::Int(num - 1)));
+            return int_result(
+                ctx,
+                num.checked_sub(1),
+                || num.wrapping_sub(1),
+                || num.saturating_sub(1),
+                || format!("decrementing {num}"),
+            );
         }
         Pallet::Float(num) => {
             return Ok(Some(Pallet::Float(num - 1.0)));
@@ -329,14 +333,515 @@ fn decrement_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
     }
 }
 
+pub static POW: StationType = StationType {
+    id: "pow",
+    alt_ids: &["^"],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(pow_procedure),
+};
+fn pow_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    let pallets = promote(pallets);
+    match (&pallets[0], &pallets[1]) {
+        (Pallet::Int(base), Pallet::Int(exp)) => {
+            let Ok(exp) = u32::try_from(*exp) else {
+                return Err(format!(
+                    "Integer exponent {exp} must be between 0 and {}, use float pallets for negative exponents",
+                    u32::MAX
+                ));
+            };
+            return int_result(
+                ctx,
+                base.checked_pow(exp),
+                || base.wrapping_pow(exp),
+                || base.saturating_pow(exp),
+                || format!("{base} to the power of {exp}"),
+            );
+        }
+        (Pallet::Float(base), Pallet::Float(exp)) => {
+            return float_result("pow", base.powf(*exp), &pallets);
+        }
+        _ => {
+            return Err(format!(
+                "Expected numerical pallets, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static SQRT: StationType = StationType {
+    id: "sqrt",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(sqrt_procedure),
+};
+fn sqrt_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    return unary_float("sqrt", f64::sqrt, &pallets);
+}
+
+pub static ABS: StationType = StationType {
+    id: "abs",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(abs_procedure),
+};
+fn abs_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::Int(num) => {
+            return int_result(
+                ctx,
+                num.checked_abs(),
+                || num.wrapping_abs(),
+                || num.saturating_abs(),
+                || format!("the absolute value of {num}"),
+            );
+        }
+        Pallet::Float(num) => {
+            return Ok(Some(Pallet::Float(num.abs())));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a numerical pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static NEGATE: StationType = StationType {
+    id: "neg",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(negate_procedure),
+};
+fn negate_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::Int(num) => {
+            return int_result(
+                ctx,
+                num.checked_neg(),
+                || num.wrapping_neg(),
+                || num.saturating_neg(),
+                || format!("the negation of {num}"),
+            );
+        }
+        Pallet::Float(num) => {
+            return Ok(Some(Pallet::Float(-num)));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a numerical pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static MIN: StationType = StationType {
+    id: "min",
+    alt_ids: &[],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(min_procedure),
+};
+fn min_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match (&pallets[0], &pallets[1]) {
+        (Pallet::Int(num1), Pallet::Int(num2)) => {
+            return Ok(Some(Pallet::Int(*num1.min(num2))));
+        }
+        (Pallet::Float(num1), Pallet::Float(num2)) => {
+            return Ok(Some(Pallet::Float(num1.min(*num2))));
+        }
+        (Pallet::Int(int), Pallet::Float(float)) | (Pallet::Float(float), Pallet::Int(int)) => {
+            // compared exactly, like floats a NaN loses
+            let pick_int = compare_int_float(*int, *float) != Some(Ordering::Greater);
+            return Ok(Some(Pallet::Float(if pick_int {
+                *int as f64
+            } else {
+                *float
+            })));
+        }
+        _ => {
+            return Err(format!(
+                "Expected numerical pallets, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static MAX: StationType = StationType {
+    id: "max",
+    alt_ids: &[],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(max_procedure),
+};
+fn max_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match (&pallets[0], &pallets[1]) {
+        (Pallet::Int(num1), Pallet::Int(num2)) => {
+            return Ok(Some(Pallet::Int(*num1.max(num2))));
+        }
+        (Pallet::Float(num1), Pallet::Float(num2)) => {
+            return Ok(Some(Pallet::Float(num1.max(*num2))));
+        }
+        (Pallet::Int(int), Pallet::Float(float)) | (Pallet::Float(float), Pallet::Int(int)) => {
+            // compared exactly, like floats a NaN loses
+            let pick_int = compare_int_float(*int, *float) != Some(Ordering::Less);
+            return Ok(Some(Pallet::Float(if pick_int {
+                *int as f64
+            } else {
+                *float
+            })));
+        }
+        _ => {
+            return Err(format!(
+                "Expected numerical pallets, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static FLOOR: StationType = StationType {
+    id: "floor",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(floor_procedure),
+};
+fn floor_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    return round_to_int(ctx, "floor", f64::floor, &pallets);
+}
+
+pub static CEIL: StationType = StationType {
+    id: "ceil",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(ceil_procedure),
+};
+fn ceil_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    return round_to_int(ctx, "ceil", f64::ceil, &pallets);
+}
+
+pub static ROUND: StationType = StationType {
+    id: "round",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(round_procedure),
+};
+fn round_procedure(pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    return round_to_int(ctx, "round", f64::round, &pallets);
+}
+
+pub static SIN: StationType = StationType {
+    id: "sin",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(sin_procedure),
+};
+fn sin_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    return unary_float("sin", f64::sin, &pallets);
+}
+
+pub static COS: StationType = StationType {
+    id: "cos",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(cos_procedure),
+};
+fn cos_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    return unary_float("cos", f64::cos, &pallets);
+}
+
+pub static TAN: StationType = StationType {
+    id: "tan",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(tan_procedure),
+};
+fn tan_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    return unary_float("tan", f64::tan, &pallets);
+}
+
+pub static ATAN2: StationType = StationType {
+    id: "atan2",
+    alt_ids: &[],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(atan2_procedure),
+};
+fn atan2_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match (as_float(&pallets[0]), as_float(&pallets[1])) {
+        (Some(y), Some(x)) => {
+            return float_result("atan2", y.atan2(x), &pallets);
+        }
+        _ => {
+            return Err(format!(
+                "Expected numerical pallets, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static LN: StationType = StationType {
+    id: "ln",
+    alt_ids: &[],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(ln_procedure),
+};
+fn ln_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    if as_float(&pallets[0]).is_some_and(|num| num <= 0.0) {
+        return Err(format!(
+            "{} is outside the domain of ln",
+            list_pallets(&pallets)
+        ));
+    }
+    return unary_float("ln", f64::ln, &pallets);
+}
+
+pub static LOG: StationType = StationType {
+    id: "log",
+    alt_ids: &[],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(log_procedure),
+};
+fn log_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match (as_float(&pallets[0]), as_float(&pallets[1])) {
+        (Some(num), Some(base)) => {
+            if num <= 0.0 || base <= 0.0 || base == 1.0 {
+                return Err(format!(
+                    "{} is outside the domain of log",
+                    list_pallets(&pallets)
+                ));
+            }
+            return float_result("log", num.log(base), &pallets);
+        }
+        _ => {
+            return Err(format!(
+                "Expected numerical pallets, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+/// Wraps the result of an integer operation, if it overflowed it's handled with the
+/// overflow policy of the run, using the wrapping or saturating result
+fn int_result(
+    ctx: &Context,
+    checked: Option<i64>,
+    wrapping: impl FnOnce() -> i64,
+    saturating: impl FnOnce() -> i64,
+    operation: impl FnOnce() -> String,
+) -> Result<Option<Pallet>, String> {
+    if let Some(num) = checked {
+        return Ok(Some(Pallet::Int(num)));
+    }
+    return match ctx.overflow {
+        Overflow::Error => Err(format!("Integer overflow in {}", operation())),
+        Overflow::Wrapping => Ok(Some(Pallet::Int(wrapping()))),
+        Overflow::Saturating => Ok(Some(Pallet::Int(saturating()))),
+    };
+}
+
+/// Converts a float with no fractional part to an integer pallet, with the overflow
+/// policy of the run if it is out of range. NaN can't be converted, and neither can
+/// infinity when wrapping
+pub(super) fn float_to_int(
+    ctx: &Context,
+    num: f64,
+    operation: impl FnOnce() -> String,
+) -> Result<Option<Pallet>, String> {
+    if num.is_nan() || (num.is_infinite() && ctx.overflow == Overflow::Wrapping) {
+        return Err(format!("Can't convert {num} to an integer"));
+    }
+    let in_range = num >= i64::MIN as f64 && num < -(i64::MIN as f64);
+    return int_result(
+        ctx,
+        in_range.then_some(num as i64),
+        || {
+            // larger floats are multiples of 2^64, which wrap to 0
+            if num.abs() < 2f64.powi(127) {
+                num as i128 as i64
+            } else {
+                0
+            }
+        },
+        // float to integer casts saturate
+        || num as i64,
+        operation,
+    );
+}
+
+/// Promotes an integer pallet to a float pallet if the other pallet is a float, so
+/// that mixed numerical pallets can be operated on
+fn promote(mut pallets: Vec<Pallet>) -> Vec<Pallet> {
+    match (&pallets[0], &pallets[1]) {
+        (Pallet::Int(num), Pallet::Float(_)) => pallets[0] = Pallet::Float(*num as f64),
+        (Pallet::Float(_), Pallet::Int(num)) => pallets[1] = Pallet::Float(*num as f64),
+        _ => {}
+    }
+    return pallets;
+}
+
+/// Compares two numerical or two boolean pallets, integers and floats are compared
+/// exactly. `None` if one of them is NaN
+fn compare(pallets: &Vec<Pallet>) -> Result<Option<Ordering>, String> {
+    return match (&pallets[0], &pallets[1]) {
+        (Pallet::Int(num1), Pallet::Int(num2)) => Ok(Some(num1.cmp(num2))),
+        (Pallet::Float(num1), Pallet::Float(num2)) => Ok(num1.partial_cmp(num2)),
+        (Pallet::Int(num1), Pallet::Float(num2)) => Ok(compare_int_float(*num1, *num2)),
+        (Pallet::Float(num1), Pallet::Int(num2)) => {
+            Ok(compare_int_float(*num2, *num1).map(Ordering::reverse))
+        }
+        (Pallet::Bool(bool1), Pallet::Bool(bool2)) => Ok(Some(bool1.cmp(bool2))),
+        _ => Err(format!(
+            "Expected two numerical or two boolean pallets, received: {}\n",
+            list_pallets(pallets)
+        )),
+    };
+}
+
+/// Compares an integer to a float without rounding the integer, `None` if the
+/// float is NaN
+fn compare_int_float(int: i64, float: f64) -> Option<Ordering> {
+    if float.is_nan() {
+        return None;
+    }
+    // the bounds of an integer are powers of two, so they are exact as floats
+    if float >= -(i64::MIN as f64) {
+        return Some(Ordering::Less);
+    }
+    if float < i64::MIN as f64 {
+        return Some(Ordering::Greater);
+    }
+    // the integer part of the float fits in an integer, the fraction breaks ties
+    let trunc = float.trunc();
+    return Some(
+        int.cmp(&(trunc as i64))
+            .then(0.0.partial_cmp(&(float - trunc))?),
+    );
+}
+
+/// Checks whether two pallets are equivalent, comparing integers and floats by
+/// exact value, in lists and maps too
+pub(super) fn equivalent(p1: &Pallet, p2: &Pallet) -> bool {
+    return match (p1, p2) {
+        (Pallet::Int(num1), Pallet::Float(num2)) | (Pallet::Float(num2), Pallet::Int(num1)) => {
+            compare_int_float(*num1, *num2) == Some(Ordering::Equal)
+        }
+        (Pallet::List(l1), Pallet::List(l2)) => {
+            l1.len() == l2.len() && l1.iter().zip(l2).all(|(a, b)| equivalent(a, b))
+        }
+        (Pallet::Map(m1), Pallet::Map(m2)) => {
+            m1.len() == m2.len()
+                && m1
+                    .iter()
+                    .zip(m2)
+                    .all(|((k1, v1), (k2, v2))| k1 == k2 && equivalent(v1, v2))
+        }
+        _ => p1 == p2,
+    };
+}
+
+/// Gets the value of a numerical pallet as a float
+fn as_float(p: &Pallet) -> Option<f64> {
+    return match p {
+        Pallet::Int(num) => Some(*num as f64),
+        Pallet::Float(num) => Some(*num),
+        _ => None,
+    };
+}
+
+/// Applies a float function to a numerical pallet, integers are converted to floats
+fn unary_float(
+    name: &str,
+    f: fn(f64) -> f64,
+    pallets: &Vec<Pallet>,
+) -> Result<Option<Pallet>, String> {
+    match as_float(&pallets[0]) {
+        Some(num) => return float_result(name, f(num), pallets),
+        None => {
+            return Err(format!(
+                "Expected a numerical pallet, received: {}\n",
+                list_pallets(pallets)
+            ));
+        }
+    }
+}
+
+/// Wraps the result of a float operation, a NaN result from operands that aren't
+/// NaN means they were outside of the operation's domain
+fn float_result(name: &str, num: f64, pallets: &Vec<Pallet>) -> Result<Option<Pallet>, String> {
+    let nan_input = pallets
+        .iter()
+        .any(|p| matches!(p, Pallet::Float(f) if f.is_nan()));
+    if num.is_nan() && !nan_input {
+        return Err(format!(
+            "{} is outside the domain of {name}",
+            list_pallets(pallets)
+        ));
+    }
+    return Ok(Some(Pallet::Float(num)));
+}
+
+/// Rounds a numerical pallet to an integer pallet, integers are unchanged
+fn round_to_int(
+    ctx: &Context,
+    name: &str,
+    f: fn(f64) -> f64,
+    pallets: &Vec<Pallet>,
+) -> Result<Option<Pallet>, String> {
+    match &pallets[0] {
+        Pallet::Int(num) => return Ok(Some(Pallet::Int(*num))),
+        Pallet::Float(num) => {
+            return float_to_int(ctx, f(*num), || format!("the {name} of {num}"));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a numerical pallet, received: {}\n",
+                list_pallets(pallets)
+            ));
+        }
+    }
+}
+
 pub static AND: StationType = StationType {
     id: "and",
-    alt_id: None,
+    alt_ids: &[],
     inputs: 2,
     output: true,
-    procedure: and_procedure,
+    procedure: Procedure::Builtin(and_procedure),
 };
-fn and_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn and_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 2, "Invalid argument count");
     match (&pallets[0], &pallets[1]) {
         (Pallet::Bool(b1), Pallet::Bool(b2)) => Ok(Some(Pallet::Bool(*b1 && *b2))),
@@ -351,12 +856,12 @@ fn and_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
 
 pub static OR: StationType = StationType {
     id: "or",
-    alt_id: None,
+    alt_ids: &[],
     inputs: 2,
     output: true,
-    procedure: or_procedure,
+    procedure: Procedure::Builtin(or_procedure),
 };
-fn or_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn or_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 2, "Invalid argument count");
     match (&pallets[0], &pallets[1]) {
         (Pallet::Bool(b1), Pallet::Bool(b2)) => Ok(Some(Pallet::Bool(*b1 || *b2))),
@@ -371,12 +876,12 @@ fn or_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
 
 pub static NOT: StationType = StationType {
     id: "not",
-    alt_id: Some("!"),
+    alt_ids: &["!"],
     inputs: 1,
     output: true,
-    procedure: not_procedure,
+    procedure: Procedure::Builtin(not_procedure),
 };
-fn not_procedure(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+fn not_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
     debug_assert!(pallets.len() >= 1, "Invalid argument count");
     match &pallets[0] {
         Pallet::Bool(b1) => Ok(Some(Pallet::Bool(!(*b1)))),
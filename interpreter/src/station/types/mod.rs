@@ -1,37 +1,77 @@
 use lazy_static::lazy_static;
-use std::fmt;
+use std::{
+    fmt,
+    io::{BufRead, Write},
+    ops::Deref,
+    sync::Arc,
+};
 
 mod control;
+mod convert;
 mod function;
 mod io;
+mod list;
+mod map;
 mod math;
+mod string;
 
 pub use control::*;
+pub use convert::*;
 pub use function::*;
 pub use io::*;
+pub use list::*;
+pub use map::*;
 pub use math::*;
+pub use string::*;
 
-use crate::Pallet;
+use crate::{Overflow, Pallet};
 
-/// Defines a builtin station and all the required information and functionality
-#[derive(Debug, PartialEq)]
+/// Defines a station type and all the required information and functionality
+#[derive(Debug)]
 pub struct StationType {
     /// Identifier
     pub id: &'static str,
-    /// Alternate identifier
-    pub alt_id: Option<&'static str>,
+    /// Alternate identifiers
+    pub alt_ids: &'static [&'static str],
     /// Minimum number of inputs required for this station to trigger its procedure
     pub inputs: usize,
     /// Does this station produce an output pallet
     pub output: bool,
-    /// Station's procedure, takes a vector of input pallets and returns an optional
-    /// pallet if successful, and an error message in a String if not
-    pub procedure: fn(pallets: Vec<Pallet>) -> Result<Option<Pallet>, String>,
+    /// Station's procedure
+    pub procedure: Procedure,
 }
 impl StationType {
+    /// Defines a host station type, for registering in a [`Registry`](super::registry::Registry).
+    /// The procedure may capture state, which must be shared through interior
+    /// mutability (a `Mutex`, for example)
+    pub fn host<F>(
+        id: &'static str,
+        alt_ids: &'static [&'static str],
+        inputs: usize,
+        output: bool,
+        procedure: F,
+    ) -> Self
+    where
+        F: Fn(Vec<Pallet>, &mut Context) -> Result<Option<Pallet>, String> + Send + Sync + 'static,
+    {
+        return StationType {
+            id,
+            alt_ids,
+            inputs,
+            output,
+            procedure: Procedure::Host(Box::new(procedure)),
+        };
+    }
+
     /// Function to check whether a station has a certain ID
     pub fn has_id(&self, query: &str) -> bool {
-        return self.id == query || (self.alt_id.is_some_and(|alt_id| alt_id == query));
+        return self.id == query || self.alt_ids.contains(&query);
+    }
+}
+impl PartialEq for StationType {
+    /// Station types are singletons, so they are compared by identity
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
     }
 }
 impl fmt::Display for StationType {
@@ -40,6 +80,82 @@ impl fmt::Display for StationType {
     }
 }
 
+/// Reference to a station type. Built in station types are static, host-defined
+/// ones are shared between the registry and the programs loaded with it, and are
+/// dropped with the last of them
+#[derive(Debug, Clone)]
+pub enum StationTypeRef {
+    Builtin(&'static StationType),
+    Host(Arc<StationType>),
+}
+impl Deref for StationTypeRef {
+    type Target = StationType;
+
+    fn deref(&self) -> &StationType {
+        return match self {
+            StationTypeRef::Builtin(t) => t,
+            StationTypeRef::Host(t) => t,
+        };
+    }
+}
+impl PartialEq for StationTypeRef {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+impl PartialEq<&StationType> for StationTypeRef {
+    fn eq(&self, other: &&StationType) -> bool {
+        **self == **other
+    }
+}
+impl fmt::Display for StationTypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// Signature of a built in procedure
+pub type BuiltinProcedure = fn(Vec<Pallet>, &mut Context) -> Result<Option<Pallet>, String>;
+
+/// Signature of a host-defined procedure
+pub type HostProcedure =
+    dyn Fn(Vec<Pallet>, &mut Context) -> Result<Option<Pallet>, String> + Send + Sync;
+
+/// A station's procedure, takes a vector of input pallets and returns an optional
+/// pallet if successful, and an error message in a String if not
+pub enum Procedure {
+    Builtin(BuiltinProcedure),
+    Host(Box<HostProcedure>),
+}
+impl Procedure {
+    pub fn call(&self, pallets: Vec<Pallet>, ctx: &mut Context) -> Result<Option<Pallet>, String> {
+        return match self {
+            Procedure::Builtin(f) => f(pallets, ctx),
+            Procedure::Host(f) => f(pallets, ctx),
+        };
+    }
+}
+impl fmt::Debug for Procedure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Procedure::Builtin(_) => write!(f, "Builtin"),
+            Procedure::Host(_) => write!(f, "Host"),
+        }
+    }
+}
+
+/// Environment that station procedures are executed in
+pub struct Context<'io> {
+    /// Where input is read from
+    pub input: &'io mut dyn BufRead,
+    /// Where output is written to
+    pub output: &'io mut dyn Write,
+    /// Arguments passed to the program
+    pub args: &'io [String],
+    /// What integer arithmetic does when it overflows
+    pub overflow: Overflow,
+}
+
 lazy_static! {
     /// A manifest of all built in station types
     pub static ref STATION_TYPES: Vec<&'static StationType> = vec![
@@ -52,6 +168,39 @@ lazy_static! {
         &io::PRINT,
         &io::PRINTLN,
         &io::READLN,
+        &io::ARGC,
+        &io::ARG,
+        &convert::TO_STRING,
+        &convert::TO_INT,
+        &convert::TO_FLOAT,
+        &convert::TO_BOOL,
+        &convert::TO_CHAR,
+        &convert::CHR,
+        &convert::ORD,
+        &list::PUSH,
+        &list::POP,
+        &list::GET,
+        &list::SET,
+        &list::LEN,
+        &list::CONCAT,
+        &list::SLICE,
+        &map::INSERT,
+        &map::LOOKUP,
+        &map::REMOVE,
+        &map::HAS_KEY,
+        &map::KEYS,
+        &map::VALUES,
+        &string::CHAR_AT,
+        &string::SUBSTRING,
+        &string::SPLIT,
+        &string::CONTAINS,
+        &string::INDEX_OF,
+        &string::REPLACE,
+        &string::TRIM,
+        &string::TO_UPPER,
+        &string::TO_LOWER,
+        &string::REVERSE,
+        &string::REPEAT,
         &math::ADD,
         &math::SUBTRACT,
         &math::MULTIPLY,
@@ -65,6 +214,21 @@ lazy_static! {
         &math::LESS_THAN_EQUAL,
         &math::INCREMENT,
         &math::DECREMENT,
+        &math::POW,
+        &math::SQRT,
+        &math::ABS,
+        &math::NEGATE,
+        &math::MIN,
+        &math::MAX,
+        &math::FLOOR,
+        &math::CEIL,
+        &math::ROUND,
+        &math::SIN,
+        &math::COS,
+        &math::TAN,
+        &math::ATAN2,
+        &math::LN,
+        &math::LOG,
         &math::AND,
         &math::NOT,
         &math::OR,
@@ -72,11 +236,12 @@ lazy_static! {
 }
 
 /// Dummy procedure that does nothing
-pub fn none_procedure(_: Vec<Pallet>) -> Result<Option<Pallet>, String> {
+pub fn none_procedure(_: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
     return Ok(None);
 }
 
 /// helper function to generate a string listing pallets, used for error messages
+#[allow(clippy::ptr_arg)]
 fn list_pallets(pallets: &Vec<Pallet>) -> String {
     let mut output = String::from("(");
     for i in 0..pallets.len() {
@@ -0,0 +1,327 @@
+use super::*;
+
+/// Length in bytes of the longest string a station can create
+const MAX_STRING_LEN: usize = 1 << 28;
+
+pub static CHAR_AT: StationType = StationType {
+    id: "char_at",
+    alt_ids: &["at"],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(char_at_procedure),
+};
+fn char_at_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match (&pallets[0], &pallets[1]) {
+        (Pallet::String(s), Pallet::Int(i)) => {
+            let c = usize::try_from(*i).ok().and_then(|i| s.chars().nth(i));
+            match c {
+                Some(c) => return Ok(Some(Pallet::Char(c))),
+                None => {
+                    return Err(format!(
+                        "Index {i} is out of bounds for a string of length {}",
+                        s.chars().count()
+                    ));
+                }
+            }
+        }
+        _ => {
+            return Err(format!(
+                "Expected a string pallet and an integer pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static SUBSTRING: StationType = StationType {
+    id: "substring",
+    alt_ids: &["substr"],
+    inputs: 3,
+    output: true,
+    procedure: Procedure::Builtin(substring_procedure),
+};
+fn substring_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 3, "Invalid argument count");
+    match (&pallets[0], &pallets[1], &pallets[2]) {
+        (Pallet::String(s), Pallet::Int(start), Pallet::Int(end)) => {
+            // the end is exclusive, so it may be the length of the string
+            let len = s.chars().count();
+            match (usize::try_from(*start), usize::try_from(*end)) {
+                (Ok(start), Ok(end)) if start <= end && end <= len => {
+                    let sub: String = s.chars().skip(start).take(end - start).collect();
+                    return Ok(Some(Pallet::String(sub)));
+                }
+                _ => {
+                    return Err(format!(
+                        "Substring from {start} to {end} is out of bounds for a string of length {len}"
+                    ));
+                }
+            }
+        }
+        _ => {
+            return Err(format!(
+                "Expected a string pallet and two integer pallets, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static SPLIT: StationType = StationType {
+    id: "split",
+    alt_ids: &["explode"],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(split_procedure),
+};
+fn split_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match (&pallets[0], text(&pallets[1])) {
+        (Pallet::String(s), Some(sep)) => {
+            let parts: Vec<Pallet> = if sep.is_empty() {
+                // splitting into characters
+                s.chars().map(|c| Pallet::String(c.to_string())).collect()
+            } else {
+                s.split(sep.as_str())
+                    .map(|part| Pallet::String(part.to_owned()))
+                    .collect()
+            };
+            return Ok(Some(Pallet::List(parts)));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a string pallet and a string or character separator pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static CONTAINS: StationType = StationType {
+    id: "contains",
+    alt_ids: &["has"],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(contains_procedure),
+};
+fn contains_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match (&pallets[0], text(&pallets[1])) {
+        (Pallet::String(s), Some(needle)) => {
+            return Ok(Some(Pallet::Bool(s.contains(needle.as_str()))));
+        }
+        (Pallet::List(list), _) => {
+            let found = list.iter().any(|p| math::equivalent(p, &pallets[1]));
+            return Ok(Some(Pallet::Bool(found)));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a string pallet and a string or character pallet, or a list pallet and any pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static INDEX_OF: StationType = StationType {
+    id: "index_of",
+    alt_ids: &["find"],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(index_of_procedure),
+};
+fn index_of_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match (&pallets[0], text(&pallets[1])) {
+        (Pallet::String(s), Some(needle)) => {
+            // character index of the first occurrence, -1 if there is none
+            let i = match s.find(needle.as_str()) {
+                Some(byte_i) => s[..byte_i].chars().count() as i64,
+                None => -1,
+            };
+            return Ok(Some(Pallet::Int(i)));
+        }
+        (Pallet::List(list), _) => {
+            let i = match list.iter().position(|p| math::equivalent(p, &pallets[1])) {
+                Some(i) => i as i64,
+                None => -1,
+            };
+            return Ok(Some(Pallet::Int(i)));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a string pallet and a string or character pallet, or a list pallet and any pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static REPLACE: StationType = StationType {
+    id: "replace",
+    alt_ids: &["repl"],
+    inputs: 3,
+    output: true,
+    procedure: Procedure::Builtin(replace_procedure),
+};
+fn replace_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 3, "Invalid argument count");
+    match (&pallets[0], text(&pallets[1]), text(&pallets[2])) {
+        (Pallet::String(s), Some(from), Some(to)) if !from.is_empty() => {
+            return Ok(Some(Pallet::String(s.replace(from.as_str(), &to))));
+        }
+        (Pallet::String(_), Some(_), Some(_)) => {
+            return Err(String::from("Can't replace an empty string"));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a string pallet and two string or character pallets, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static TRIM: StationType = StationType {
+    id: "trim",
+    alt_ids: &["strip"],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(trim_procedure),
+};
+fn trim_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::String(s) => return Ok(Some(Pallet::String(s.trim().to_owned()))),
+        _ => {
+            return Err(format!(
+                "Expected a string pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static TO_UPPER: StationType = StationType {
+    id: "to_upper",
+    alt_ids: &["upper"],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(to_upper_procedure),
+};
+fn to_upper_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::String(s) => return Ok(Some(Pallet::String(s.to_uppercase()))),
+        // characters that have no single uppercase character are unchanged
+        Pallet::Char(c) => {
+            let mut upper = c.to_uppercase();
+            return Ok(Some(Pallet::Char(match (upper.next(), upper.next()) {
+                (Some(u), None) => u,
+                _ => *c,
+            })));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a string or character pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static TO_LOWER: StationType = StationType {
+    id: "to_lower",
+    alt_ids: &["lower"],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(to_lower_procedure),
+};
+fn to_lower_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::String(s) => return Ok(Some(Pallet::String(s.to_lowercase()))),
+        // characters that have no single lowercase character are unchanged
+        Pallet::Char(c) => {
+            let mut lower = c.to_lowercase();
+            return Ok(Some(Pallet::Char(match (lower.next(), lower.next()) {
+                (Some(l), None) => l,
+                _ => *c,
+            })));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a string or character pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static REVERSE: StationType = StationType {
+    id: "reverse",
+    alt_ids: &["rev"],
+    inputs: 1,
+    output: true,
+    procedure: Procedure::Builtin(reverse_procedure),
+};
+fn reverse_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 1, "Invalid argument count");
+    match &pallets[0] {
+        Pallet::String(s) => return Ok(Some(Pallet::String(s.chars().rev().collect()))),
+        Pallet::List(list) => {
+            return Ok(Some(Pallet::List(list.iter().rev().cloned().collect())));
+        }
+        _ => {
+            return Err(format!(
+                "Expected a string or list pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+pub static REPEAT: StationType = StationType {
+    id: "repeat",
+    alt_ids: &["rep"],
+    inputs: 2,
+    output: true,
+    procedure: Procedure::Builtin(repeat_procedure),
+};
+fn repeat_procedure(pallets: Vec<Pallet>, _: &mut Context) -> Result<Option<Pallet>, String> {
+    debug_assert!(pallets.len() >= 2, "Invalid argument count");
+    match (text(&pallets[0]), &pallets[1]) {
+        (Some(s), Pallet::Int(n)) => {
+            let Ok(count) = usize::try_from(*n) else {
+                return Err(format!("Can't repeat a string {n} times"));
+            };
+            match s.len().checked_mul(count) {
+                Some(len) if len <= MAX_STRING_LEN => {
+                    return Ok(Some(Pallet::String(s.repeat(count))));
+                }
+                _ => {
+                    return Err(format!(
+                        "Repeating a string of {} bytes {n} times is longer than the maximum of {MAX_STRING_LEN} bytes",
+                        s.len()
+                    ));
+                }
+            }
+        }
+        _ => {
+            return Err(format!(
+                "Expected a string or character pallet and an integer pallet, received: {}\n",
+                list_pallets(&pallets)
+            ));
+        }
+    }
+}
+
+/// Gets the text of a string or character pallet
+fn text(p: &Pallet) -> Option<String> {
+    return match p {
+        Pallet::String(s) => Some(s.clone()),
+        Pallet::Char(c) => Some(c.to_string()),
+        _ => None,
+    };
+}
@@ -0,0 +1,123 @@
+use std::io::Write;
+
+use crate::{runtime::StepLog, util::SourceSpan, Pallet};
+
+/// Writes the record of one step as a line of JSON, in the form:
+///
+/// ```text
+/// {"step":0,"fired":[{"function":"main","index":2,"station":"add",
+///  "loc":{"line":1,"col":8,"len":5},"inputs":[...],"output":...}],
+///  "moved":[{"function":"main","pallet":...,"bay":[2,7]}]}
+/// ```
+///
+/// Lines are 1-indexed and columns 0-indexed, the same as in error messages.
+/// Bays are in the form `[station_index, priority]`
+pub fn write_step(out: &mut dyn Write, step: u64, log: &StepLog) -> std::io::Result<()> {
+    write!(out, "{{\"step\":{step},\"fired\":[")?;
+    for (i, firing) in log.fired.iter().enumerate() {
+        if i > 0 {
+            write!(out, ",")?;
+        }
+        let station = firing.station();
+        write!(
+            out,
+            "{{\"function\":{},\"index\":{},\"station\":{},\"loc\":{},\"inputs\":[",
+            json_string(&firing.function.name),
+            firing.station,
+            json_string(station.s_type.id),
+            json_span(&station.loc),
+        )?;
+        for (j, pallet) in firing.inputs.iter().enumerate() {
+            if j > 0 {
+                write!(out, ",")?;
+            }
+            write!(out, "{}", json_pallet(pallet))?;
+        }
+        write!(out, "],\"output\":")?;
+        match &firing.output {
+            Some(pallet) => write!(out, "{}}}", json_pallet(pallet))?,
+            None => write!(out, "null}}")?,
+        }
+    }
+    write!(out, "],\"moved\":[")?;
+    for (i, m) in log.moved.iter().enumerate() {
+        if i > 0 {
+            write!(out, ",")?;
+        }
+        write!(
+            out,
+            "{{\"function\":{},\"pallet\":{},\"bay\":[{},{}]}}",
+            json_string(&m.function.name),
+            json_pallet(&m.pallet),
+            m.dest.0,
+            m.dest.1
+        )?;
+    }
+    writeln!(out, "]}}")?;
+    return Ok(());
+}
+
+/// Formats a string as a JSON string literal
+fn json_string(s: &str) -> String {
+    let mut output = String::with_capacity(s.len() + 2);
+    output.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    return output;
+}
+
+/// Formats a source span as a JSON object
+fn json_span(loc: &SourceSpan) -> String {
+    return format!(
+        "{{\"line\":{},\"col\":{},\"len\":{}}}",
+        loc.pos.line + 1,
+        loc.pos.col,
+        loc.len
+    );
+}
+
+/// Formats a pallet as a JSON object with its type and value
+fn json_pallet(pallet: &Pallet) -> String {
+    return match pallet {
+        Pallet::Empty => String::from("{\"type\":\"empty\"}"),
+        Pallet::Bool(b) => format!("{{\"type\":\"bool\",\"value\":{b}}}"),
+        Pallet::Char(c) => format!(
+            "{{\"type\":\"char\",\"value\":{}}}",
+            json_string(&c.to_string())
+        ),
+        Pallet::String(s) => format!("{{\"type\":\"string\",\"value\":{}}}", json_string(s)),
+        Pallet::Int(i) => format!("{{\"type\":\"int\",\"value\":{i}}}"),
+        Pallet::Float(f) if f.is_finite() => {
+            format!("{{\"type\":\"float\",\"value\":{f:?}}}")
+        }
+        // JSON has no representation of infinity or NaN
+        Pallet::Float(f) => format!(
+            "{{\"type\":\"float\",\"value\":{}}}",
+            json_string(&f.to_string())
+        ),
+        Pallet::List(l) => {
+            let items: Vec<String> = l.iter().map(json_pallet).collect();
+            format!("{{\"type\":\"list\",\"value\":[{}]}}", items.join(","))
+        }
+        Pallet::Map(m) => {
+            let items: Vec<String> = m
+                .iter()
+                .map(|(k, v)| {
+                    let k = json_pallet(&Pallet::from(k.clone()));
+                    format!("[{k},{}]", json_pallet(v))
+                })
+                .collect();
+            format!("{{\"type\":\"map\",\"value\":[{}]}}", items.join(","))
+        }
+    };
+}
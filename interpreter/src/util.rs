@@ -18,6 +18,7 @@ impl SourcePos {
         SourceSpan::new(*self, len)
     }
 }
+#[allow(clippy::from_over_into)]
 impl Into<SourceSpan> for SourcePos {
     fn into(self) -> SourceSpan {
         SourceSpan::new(self, 1)
@@ -94,67 +95,49 @@ impl std::fmt::Display for Direction {
     }
 }
 
-// for easy on/off debug statements
+// for easy on/off debug statements, takes the run options as the first argument
 #[macro_export]
 macro_rules! debug {
-	($level:literal, $msg:literal, $($args:expr),*) => {
-		unsafe {
-			use crate::{COLOR_OUTPUT, DEBUG_LEVEL};
-			if $level <= DEBUG_LEVEL {
-                print!("{: <1$}", "", ($level-1) * 2);
-				if $level > 1 && COLOR_OUTPUT {
-					print!("\x1b[90m");
-					print!($msg, $($args),*);
-					println!("\x1b[0m");
-				} else {
-					println!($msg, $($args),*);
-				}
-			}
-		}
-    };
-    ($level:literal, $msg:literal) => {
-		unsafe {
-			use crate::{COLOR_OUTPUT, DEBUG_LEVEL};
-			if $level <= DEBUG_LEVEL {
-                print!("{: <1$}", "", ($level-1) * 2);
-				if $level > 1 && COLOR_OUTPUT {
-					print!("\x1b[90m");
-					print!($msg);
-					println!("\x1b[0m");
-				} else {
-					println!($msg);
-				}
-			}
-		}
+    ($options:expr, $level:literal, $($args:tt)*) => {
+        if $level <= $options.debug_level {
+            print!("{: <1$}", "", ($level - 1) * 2);
+            if $level > 1 && $options.color {
+                print!("\x1b[90m");
+                print!($($args)*);
+                println!("\x1b[0m");
+            } else {
+                println!($($args)*);
+            }
+        }
     };
 }
-//pub(crate) use debug;
 
+// prints an error message, takes whether to use color as the first argument
 #[macro_export]
 macro_rules! print_cli_err {
-    ($msg:literal, $($args:expr),*) => {
-        unsafe {
-            if COLOR_OUTPUT {
-                print!("\x1b[31m");
-                print!($msg, $($args),*);
-                println!("\x1b[0m");
-            } else {
-                print!("ERROR! ");
-                println!($msg, $($args),*);
-            }
+    ($color:expr, $($args:tt)*) => {
+        if $color {
+            print!("\x1b[31m");
+            print!($($args)*);
+            println!("\x1b[0m");
+        } else {
+            print!("ERROR! ");
+            println!($($args)*);
         }
     };
-    ($msg:literal) => {
-        unsafe {
-            if COLOR_OUTPUT {
-                print!("\x1b[31m");
-                print!($msg);
-                println!("\x1b[0m");
-            } else {
-                print!("ERROR! ");
-                println!($msg);
-            }
+}
+
+// prints a warning message, takes whether to use color as the first argument
+#[macro_export]
+macro_rules! print_cli_warn {
+    ($color:expr, $($args:tt)*) => {
+        if $color {
+            print!("\x1b[33m");
+            print!($($args)*);
+            println!("\x1b[0m");
+        } else {
+            print!("WARNING! ");
+            println!($($args)*);
         }
     };
 }
-//pub(crate) use print_cli_err;
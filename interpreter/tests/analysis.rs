@@ -0,0 +1,137 @@
+use interpreter::{load, Error, ErrorType, RunOptions};
+
+/// A diagnostic's type, 1-indexed line, column, length and message
+type Diagnostic = (ErrorType, usize, usize, usize, String);
+
+fn diagnostic(e: &Error) -> Diagnostic {
+    return (
+        e.t,
+        e.loc.pos.line + 1,
+        e.loc.pos.col,
+        e.loc.len,
+        e.msg.clone(),
+    );
+}
+
+/// Preprocesses a program that must load, returns its warnings
+fn warnings(src: &str) -> Vec<Diagnostic> {
+    let program = load(src, &RunOptions::default()).unwrap_or_else(|errors| {
+        panic!("{}", errors[0].msg);
+    });
+    return program.warnings().iter().map(diagnostic).collect();
+}
+
+/// Preprocesses a program that must fail, returns its errors
+fn errors(src: &str) -> Vec<Diagnostic> {
+    let Err(errors) = load(src, &RunOptions::default()) else {
+        panic!("program loaded without errors");
+    };
+    return errors.iter().map(diagnostic).collect();
+}
+
+fn warning(line: usize, col: usize, len: usize, msg: &str) -> Diagnostic {
+    return (ErrorType::Warning, line, col, len, msg.to_owned());
+}
+
+fn syntax_error(line: usize, col: usize, len: usize, msg: &str) -> Diagnostic {
+    return (ErrorType::SyntaxError, line, col, len, msg.to_owned());
+}
+
+#[test]
+fn unreachable_station() {
+    assert_eq!(
+        warnings("[main]═─[println]\n\n[println]\n"),
+        [warning(
+            3,
+            0,
+            9,
+            "Station 'println' is unreachable, no belts lead to it from [main] or a function input"
+        )]
+    );
+}
+
+#[test]
+fn belts_out_of_a_station_without_output() {
+    assert_eq!(
+        warnings("[main]═─[println]═─[println]\n"),
+        [warning(
+            1,
+            8,
+            9,
+            "Station 'println' doesn't output pallets, its outgoing belts are never used"
+        )]
+    );
+}
+
+#[test]
+fn dangling_output() {
+    assert_eq!(
+        warnings("[main]═─{1}\n"),
+        [warning(1, 8, 3, "Output of station 'assign' goes nowhere")]
+    );
+}
+
+#[test]
+fn too_few_input_belts() {
+    assert_eq!(
+        warnings("[main]═─{1}═─[+]═─[println]\n"),
+        [warning(
+            1,
+            13,
+            3,
+            "Station 'add' needs 2 input(s) to trigger, but only 1 belt(s) lead to it"
+        )]
+    );
+}
+
+#[test]
+fn function_never_invoked() {
+    assert_eq!(
+        warnings("[main]═─[println]\n\n[$f.0]═─[println]\n"),
+        [warning(
+            3,
+            0,
+            6,
+            "Function 'f' is defined but never invoked"
+        )]
+    );
+}
+
+#[test]
+fn result_of_function_without_output() {
+    assert_eq!(
+        warnings("[main]═─[$f]═─[println]\n\n[$f.0]═─[println]\n"),
+        [warning(
+            1,
+            8,
+            4,
+            "Function 'f' has no output station, the result of this invocation is never produced"
+        )]
+    );
+}
+
+#[test]
+fn argument_gap() {
+    assert_eq!(
+        errors("[main]═─[$f]\n\n[$f.1]═─[$f.out]\n"),
+        [syntax_error(
+            3,
+            0,
+            6,
+            "Function 'f' is missing argument 0, arguments must be numbered from 0 without gaps"
+        )]
+    );
+}
+
+#[test]
+fn undefined_function() {
+    assert_eq!(
+        errors("[main]═─[$g]═─[println]\n"),
+        [syntax_error(
+            1,
+            8,
+            4,
+            "Function 'g' is never defined, it has no input stations"
+        )]
+    );
+}
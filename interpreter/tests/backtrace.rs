@@ -0,0 +1,101 @@
+use interpreter::{run, Error, ErrorType, RunOptions};
+
+/// Runs a program that must fail at runtime, returns its error
+fn runtime_error(src: &str) -> Error {
+    let mut out: Vec<u8> = Vec::new();
+    let mut errors = run(
+        src,
+        &mut RunOptions {
+            output: Box::new(&mut out),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    let e = errors.remove(0);
+    assert_eq!(e.t, ErrorType::RuntimeError);
+    return e;
+}
+
+/// The lines of an error message after the source excerpt
+fn message(e: &Error, src: &str) -> Vec<String> {
+    let msg = e.pretty_msg(&src.to_owned());
+    let start = msg.find(&e.msg).unwrap();
+    return msg[start..].lines().map(String::from).collect();
+}
+
+#[test]
+fn nested_invocations_are_listed_most_recent_first() {
+    // g divides 0 by itself
+    let src = "\
+[main]═─{0}═─[$f]
+
+[$f.0]═─[$g]
+
+[$g.0]═─[]═─[/]
+         ╚──┘
+";
+    let e = runtime_error(src);
+    assert_eq!(e.msg, "Attempted divide by zero");
+    assert_eq!((e.loc.pos.line, e.loc.pos.col, e.loc.len), (4, 12, 3));
+
+    let calls: Vec<(&str, &str, usize, usize)> = e
+        .backtrace
+        .iter()
+        .map(|c| {
+            (
+                c.function.as_str(),
+                c.caller.as_str(),
+                c.loc.pos.line,
+                c.loc.pos.col,
+            )
+        })
+        .collect();
+    assert_eq!(calls, [("g", "f", 2, 8), ("f", "main", 0, 13)]);
+    assert_eq!(
+        message(&e, src),
+        [
+            "Attempted divide by zero",
+            "Backtrace (most recent invocation first):",
+            "  'g' invoked @ 3:8-12 in 'f'",
+            "  'f' invoked @ 1:13-17 in 'main'",
+        ]
+    );
+}
+
+#[test]
+fn deep_backtraces_are_shortened_in_the_middle() {
+    // r invokes itself with n - 1 and divides n by itself, failing at n = 0
+    let src = "\
+[main]═─{25}═─[$r]
+
+[$r.0]═─[]═─[]═─[/]
+        ║    ╚──┘
+        ╚═─[--]═─[$r]
+";
+    let e = runtime_error(src);
+    assert_eq!(e.msg, "Attempted divide by zero");
+    assert_eq!(e.backtrace.len(), 26);
+
+    let recursive = "  'r' invoked @ 5:17-21 in 'r'";
+    let mut expected = vec![
+        "Attempted divide by zero",
+        "Backtrace (most recent invocation first):",
+    ];
+    expected.extend([recursive; 10]);
+    expected.push("  ... 6 more invocations");
+    expected.extend([recursive; 9]);
+    expected.push("  'r' invoked @ 1:14-18 in 'main'");
+    assert_eq!(message(&e, src), expected);
+}
+
+#[test]
+fn errors_in_main_have_no_backtrace() {
+    let src = "\
+[main]═─{0}═─[]═─[/]
+              ╚──┘
+";
+    let e = runtime_error(src);
+    assert!(e.backtrace.is_empty());
+    assert_eq!(message(&e, src), ["Attempted divide by zero"]);
+}
@@ -0,0 +1,86 @@
+use interpreter::{load, ErrorType, RunOptions};
+
+/// Preprocesses a program that must fail, returns the type, 1-indexed line,
+/// column, length and message of every error
+fn errors(src: &str) -> Vec<(ErrorType, usize, usize, usize, String)> {
+    let Err(errors) = load(src, &RunOptions::default()) else {
+        panic!("program loaded without errors");
+    };
+    return errors
+        .into_iter()
+        .map(|e| (e.t, e.loc.pos.line + 1, e.loc.pos.col, e.loc.len, e.msg))
+        .collect();
+}
+
+#[test]
+fn belt_turning_onto_a_shorter_line_is_unattached() {
+    // both used to index past the end of the line the belt turned onto
+    let unattached = |line, col| {
+        (
+            ErrorType::SyntaxError,
+            line,
+            col,
+            1,
+            String::from("Unattached conveyor belt"),
+        )
+    };
+    assert_eq!(errors("\n[main]═─┘\n"), [unattached(2, 8)]);
+    assert_eq!(errors("[main]═─┐\n\n"), [unattached(1, 8)]);
+}
+
+#[test]
+fn every_preprocessing_error_is_reported() {
+    let src = "\
+[main]═─[foo]
+
+[main]═─{1,}═─[println]
+
+[main]═─┐
+
+[main]═[println]
+
+[$f.x]
+";
+    let mut found = errors(src);
+    found.sort_by_key(|e| (e.1, e.2));
+    assert_eq!(
+        found,
+        [
+            (
+                ErrorType::IdentifierError,
+                1,
+                8,
+                5,
+                String::from("Failed to find station type with identifier \"foo\"")
+            ),
+            (
+                ErrorType::SyntaxError,
+                3,
+                11,
+                1,
+                String::from("Invalid assignment literal")
+            ),
+            (
+                ErrorType::SyntaxError,
+                5,
+                8,
+                1,
+                String::from("Unattached conveyor belt")
+            ),
+            (
+                ErrorType::SyntaxError,
+                7,
+                7,
+                1,
+                String::from("Invalid conveyor belt")
+            ),
+            (
+                ErrorType::SyntaxError,
+                9,
+                0,
+                6,
+                String::from("Invalid function suffix, must be 'out' or a positive integer")
+            ),
+        ]
+    );
+}
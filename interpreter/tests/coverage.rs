@@ -0,0 +1,157 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use interpreter::{run, Coverage, RunOptions};
+use predicates::prelude::*;
+
+/// A gate that never lets its pallet through, and a function that is never invoked
+const SRC: &str = "\
+[main]═─[]═─{1}═─[gate]═─[println]
+         ╚──{false}═┘
+
+[$f.0]═─[println]
+";
+
+/// A function that divides by zero two invocations deep
+const FAILING: &str = "\
+[main]═─{0}═─[$f]
+
+[$f.0]═─[$g]
+
+[$g.0]═─[]═─[/]
+         ╚──┘
+";
+
+/// Runs a program with coverage, returns the coverage of the run or of the error
+/// it stopped with
+fn coverage(src: &str) -> Coverage {
+    let mut out: Vec<u8> = Vec::new();
+    let res = run(
+        src,
+        &mut RunOptions {
+            output: Box::new(&mut out),
+            coverage: true,
+            ..Default::default()
+        },
+    );
+    return match res {
+        Ok(report) => report.coverage.unwrap(),
+        Err(mut errors) => *errors.remove(0).coverage.unwrap(),
+    };
+}
+
+fn write(f: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> String {
+    let mut out: Vec<u8> = Vec::new();
+    f(&mut out).unwrap();
+    return String::from_utf8(out).unwrap();
+}
+
+#[test]
+fn listing_marks_stations_that_never_triggered() {
+    let coverage = coverage(SRC);
+    let listing = write(|out| coverage.write_listing(SRC, false, out));
+    assert_eq!(
+        listing,
+        "   1 | [main]═─[]═─{1}═─[gate]═─[println]
+     |                          ^^^^^^^^^ never triggered
+   2 |          ╚──{false}═┘
+   3 | 
+   4 | [$f.0]═─[println]
+     | ^^^^^^  ^^^^^^^^^ never triggered
+   5 | 
+"
+    );
+}
+
+#[test]
+fn summary_counts_stations_and_belts() {
+    let coverage = coverage(SRC);
+    let summary = write(|out| coverage.write_summary(out));
+    assert_eq!(
+        summary,
+        "
+======Coverage=======
+ stations 5/8 triggered (62.5%)
+ belts    5/7 carried pallets (71.4%)
+
+ never triggered:
+   println @ 1:25-34 in 'main'
+   func_input @ 4:0-6 in 'f'
+   println @ 4:8-17 in 'f'
+
+ never carried pallets:
+   gate @ 1:17-23 to println @ 1:25-34 bay 0 in 'main'
+   func_input @ 4:0-6 to println @ 4:8-17 bay 0 in 'f'
+=====================
+"
+    );
+}
+
+#[test]
+fn lcov_records_functions_belts_and_lines() {
+    let coverage = coverage(SRC);
+    let lcov = write(|out| coverage.write_lcov("prog.factory", out));
+    let expected = [
+        "TN:",
+        "SF:prog.factory",
+        "FN:1,main",
+        "FN:4,f",
+        "FNDA:1,main",
+        "FNDA:0,f",
+        "FNF:2",
+        "FNH:1",
+        // belts, by the line and column they leave their station from
+        "BRDA:1,0,0,1",
+        "BRDA:1,8,1,1",
+        "BRDA:1,8,2,1",
+        "BRDA:1,12,3,1",
+        "BRDA:1,17,4,-",
+        "BRDA:2,12,5,1",
+        "BRDA:4,0,6,-",
+        "BRF:7",
+        "BRH:5",
+        // a line counts as often as its least triggered station
+        "DA:1,0",
+        "DA:2,1",
+        "DA:4,0",
+        "LF:3",
+        "LH:1",
+        "end_of_record",
+    ];
+    assert_eq!(lcov.lines().collect::<Vec<&str>>(), expected);
+}
+
+#[test]
+fn failed_runs_keep_their_coverage() {
+    let coverage = coverage(FAILING);
+    // the division failed, so it never finished triggering
+    let never: Vec<(&str, String)> = coverage
+        .stations
+        .iter()
+        .filter(|s| s.fires == 0)
+        .map(|s| (s.station, s.loc.to_string()))
+        .collect();
+    assert_eq!(never, [("div", String::from("5:12-15"))]);
+    let invocations: Vec<u64> = coverage.functions.iter().map(|f| f.invocations).collect();
+    assert_eq!(invocations, [1, 1, 1]);
+}
+
+#[test]
+fn failed_runs_write_coverage_from_the_command_line() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let file = dir.child("failing.factory");
+    file.write_str(FAILING).unwrap();
+    let lcov = dir.child("failing.lcov");
+
+    Command::cargo_bin("factory")
+        .unwrap()
+        .args(["--no-color", "--coverage", "--lcov"])
+        .arg(lcov.path())
+        .arg(file.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Attempted divide by zero"))
+        .stdout(predicate::str::contains(" stations 7/8 triggered (87.5%)"));
+    lcov.assert(predicate::str::contains("FNH:3\n"));
+    lcov.assert(predicate::str::contains("DA:5,0\n"));
+    lcov.assert(predicate::str::ends_with("end_of_record\n"));
+}
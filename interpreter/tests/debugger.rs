@@ -0,0 +1,42 @@
+use assert_cmd::Command;
+use std::path::PathBuf;
+
+/// Runs an example in the debugger with the given commands, returns everything it
+/// printed
+fn debug_example(name: &str, commands: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../examples")
+        .join(format!("{name}.factory"));
+    let output = Command::cargo_bin("factory")
+        .unwrap()
+        .arg("debug")
+        .arg("--no-color")
+        .arg(path)
+        .write_stdin(commands)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    return String::from_utf8(output.stdout).unwrap();
+}
+
+#[test]
+fn breakpoint_on_a_source_position_pauses_there() {
+    // the add station spans 2:17-20 (the file starts with an empty line)
+    let out = debug_example("add", "b 2:18\nc\nc\n");
+
+    let set = out.find("Breakpoint 0 set on station @ 2:18").unwrap();
+    let hit = out.find("Hit breakpoint 0 on station @ 2:18").unwrap();
+    let fired = out.find("step 3:\n  add @ 2:17-20").unwrap();
+    let printed = out.find("(step 3) 3\n").unwrap();
+    assert!(set < hit && hit < fired && fired < printed);
+    assert!(out.ends_with("Program finished after 4 steps\n"));
+}
+
+#[test]
+fn breakpoint_outside_a_station_is_rejected() {
+    let out = debug_example("add", "b 2:16\nc\n");
+
+    assert!(out.contains("No station found at 2:16"));
+    assert!(!out.contains("Hit breakpoint"));
+    assert!(out.ends_with("Program finished after 4 steps\n"));
+}
@@ -0,0 +1,98 @@
+use interpreter::{load_with_registry, Error, ErrorType, Registry, RunOptions, StationType};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Runs a program with state dumps until it is interrupted, returns the error it
+/// stopped with. The `interrupt` station raises the interrupt flag and passes its
+/// input through
+fn interrupted(src: &str, flag: Arc<AtomicBool>) -> Error {
+    let raise = Arc::clone(&flag);
+    let mut registry = Registry::new();
+    registry
+        .register(StationType::host(
+            "interrupt",
+            &[],
+            1,
+            true,
+            move |pallets, _| {
+                raise.store(true, Ordering::Relaxed);
+                Ok(Some(pallets[0].clone()))
+            },
+        ))
+        .unwrap();
+
+    let mut out: Vec<u8> = Vec::new();
+    let mut options = RunOptions {
+        output: Box::new(&mut out),
+        dump_state: true,
+        interrupt: Some(flag),
+        ..Default::default()
+    };
+    let program = load_with_registry(src, &registry, &options).unwrap();
+    let e = program.run(&mut options).unwrap_err();
+    assert_eq!(e.t, ErrorType::LimitError);
+    return e;
+}
+
+#[test]
+fn interrupt_before_the_first_step() {
+    let src = "\
+[main]═─[]═─{1}═─[+]═──[println]
+         ╚──{2}═──┘
+";
+    let e = interrupted(src, Arc::new(AtomicBool::new(true)));
+    assert_eq!(
+        e.msg,
+        "Interrupted after 0 steps, 1 pallet(s) still moving and 0 function invocation(s) still running"
+    );
+    assert_eq!(
+        e.state.unwrap(),
+        "\
+State after 0 steps:
+'main'
+  Pallet< > moving to joint @ 1:8-10 bay 3"
+    );
+}
+
+#[test]
+fn interrupt_inside_a_function() {
+    // the list reaches push before the interrupted pallet does
+    let src = "\
+[main]═─{5}═─[$f]═─[println]
+
+[$f.0]═─[]═─[]═─[interrupt]═─[push]═─[$f.out]
+         ╚═─{[1, 2]}═──────────┘
+";
+    let e = interrupted(src, Arc::new(AtomicBool::new(false)));
+    assert_eq!(
+        e.msg,
+        "Interrupted after 4 steps, 1 pallet(s) still moving and 1 function invocation(s) still running"
+    );
+    assert_eq!(
+        e.state.unwrap(),
+        "\
+State after 4 steps:
+'main'
+  'f' invoked @ 1:13-17
+    Pallet<i:5> moving to push @ 3:29-35 bay 4
+    push @ 3:29-35 waiting with bay 1 filled (Pallet<l:[i:1, i:2]>), bay 4 empty"
+    );
+}
+
+#[test]
+fn no_state_without_dump_state() {
+    let mut out: Vec<u8> = Vec::new();
+    let errors = interpreter::run(
+        "[main]═─{1}═─[println]\n",
+        &mut RunOptions {
+            output: Box::new(&mut out),
+            interrupt: Some(Arc::new(AtomicBool::new(true))),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(errors[0].t, ErrorType::LimitError);
+    assert!(errors[0].state.is_none());
+}
@@ -0,0 +1,93 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use std::{fs, path::PathBuf};
+
+/// Every example with the input it reads and the number of steps it takes
+const EXAMPLES: &[(&str, &str, u64)] = &[
+    ("99_bottles", "", 122),
+    ("add", "", 4),
+    ("fibonacci", "", 460),
+    ("fizzbuzz", "", 1301),
+    ("for_loop", "", 41),
+    ("greet", "Bob\n", 6),
+    ("hello_world", "", 2),
+    ("hello_world_backwards", "", 2),
+    ("hello_world_pretty", "", 2),
+];
+
+/// Runs a program with benchmarking, returns its output without the benchmark and
+/// the step count
+fn run_benchmarked(path: &PathBuf, input: &str) -> (String, u64) {
+    let output = Command::cargo_bin("factory")
+        .unwrap()
+        .arg("--no-color")
+        .arg("--benchmark")
+        .arg(path)
+        .write_stdin(input)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{} failed", path.display());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let (out, benchmark) = stdout
+        .split_once("\n======Benchmark======")
+        .expect("missing benchmark");
+    let steps = benchmark
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("steps"))
+        .expect("missing step count")
+        .trim()
+        .parse()
+        .unwrap();
+    return (out.to_owned(), steps);
+}
+
+#[test]
+fn examples_keep_output_and_step_count() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    for (name, input, steps) in EXAMPLES {
+        let path = root.join("../examples").join(format!("{name}.factory"));
+        let expected = fs::read_to_string(root.join(format!("tests/expected/{name}.out"))).unwrap();
+
+        let (out, actual_steps) = run_benchmarked(&path, input);
+        assert_eq!(out, expected, "output of {name} changed");
+        assert_eq!(actual_steps, *steps, "step count of {name} changed");
+    }
+}
+
+#[test]
+fn every_example_is_pinned() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../examples");
+    for entry in fs::read_dir(root).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        assert!(
+            EXAMPLES.iter().any(|(n, _, _)| *n == name),
+            "example {name} has no expected output"
+        );
+    }
+}
+
+#[test]
+fn deep_recursion_without_depth_limit() {
+    // counts down from 1500 by invoking itself, deeper than the old recursion limit
+    let src = "\
+[main]═─{1500}═─[$cd]
+
+[$cd.0]═─[]═──[println]
+         ║╚──[--]═────────┐
+         │                │
+         └─[]═─[<]═─[gate]┘
+            ║   │      ║
+            └{0}╝      └─[$cd]
+";
+    let file = assert_fs::NamedTempFile::new("countdown.factory").unwrap();
+    file.write_str(src).unwrap();
+
+    let (out, steps) = run_benchmarked(&file.path().to_path_buf(), "");
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 1501);
+    assert_eq!(lines.first(), Some(&"1500"));
+    assert_eq!(lines.last(), Some(&"0"));
+    assert_eq!(steps, 7506);
+}
@@ -0,0 +1,87 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use interpreter::{run, ErrorType, RunOptions, RunReport};
+use predicates::prelude::*;
+
+/// Runs a program that gives a pallet to the exit station
+fn exit_with(literal: &str) -> Result<RunReport, (ErrorType, String)> {
+    let src = format!("[main]═─{literal}═─[exit]\n");
+    let mut out: Vec<u8> = Vec::new();
+    return run(
+        &src,
+        &mut RunOptions {
+            output: Box::new(&mut out),
+            ..Default::default()
+        },
+    )
+    .map_err(|errors| (errors[0].t, errors[0].msg.clone()));
+}
+
+#[test]
+fn integer_exit_status_passes_through() {
+    for status in [0, 3, 255] {
+        let report = exit_with(&format!("{{{status}}}")).unwrap();
+        assert!(report.exited);
+        assert_eq!(report.exit_status, status);
+    }
+}
+
+#[test]
+fn out_of_range_exit_status_is_an_error() {
+    for (literal, received) in [("{256}", "256"), ("{0}═─[--]", "-1")] {
+        let (t, msg) = exit_with(literal).unwrap_err();
+        assert_eq!(t, ErrorType::RuntimeError);
+        assert_eq!(
+            msg,
+            format!("Exit status must be between 0 and 255, received {received}")
+        );
+    }
+}
+
+#[test]
+fn other_pallets_exit_with_zero() {
+    for literal in ["{\"a\"}", "{2.5}", "{true}", "{[3]}"] {
+        let report = exit_with(literal).unwrap();
+        assert!(report.exited, "{literal}");
+        assert_eq!(report.exit_status, 0, "{literal}");
+    }
+    // main's empty pallet
+    let report = exit_with("[]").unwrap();
+    assert!(report.exited);
+    assert_eq!(report.exit_status, 0);
+}
+
+#[test]
+fn programs_that_end_without_exiting_report_it() {
+    let mut out: Vec<u8> = Vec::new();
+    let report = run(
+        "[main]═─{3}═─[println]\n",
+        &mut RunOptions {
+            output: Box::new(&mut out),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(!report.exited);
+    assert_eq!(report.exit_status, 0);
+}
+
+#[test]
+fn exit_status_is_the_process_exit_code() {
+    let file = assert_fs::NamedTempFile::new("exit.factory").unwrap();
+    file.write_str("[main]═─{3}═─[exit]\n").unwrap();
+    Command::cargo_bin("factory")
+        .unwrap()
+        .arg(file.path())
+        .assert()
+        .code(3);
+
+    file.write_str("[main]═─{300}═─[exit]\n").unwrap();
+    Command::cargo_bin("factory")
+        .unwrap()
+        .arg("--no-color")
+        .arg(file.path())
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("received 300"));
+}
@@ -0,0 +1,76 @@
+use assert_cmd::Command;
+use interpreter::{run, ErrorType, RunOptions};
+use predicates::prelude::*;
+use std::{fs, path::PathBuf, time::Duration};
+
+/// Path to an example program
+fn example(name: &str) -> PathBuf {
+    return PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../examples")
+        .join(format!("{name}.factory"));
+}
+
+/// Runs fizzbuzz with the given options, returns the error it stopped with and
+/// what it printed before that
+fn run_fizzbuzz(options: RunOptions) -> (ErrorType, String, String) {
+    let src = fs::read_to_string(example("fizzbuzz")).unwrap();
+    let mut out: Vec<u8> = Vec::new();
+    let mut errors = run(
+        &src,
+        &mut RunOptions {
+            output: Box::new(&mut out),
+            ..options
+        },
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    let e = errors.remove(0);
+    return (e.t, e.msg, String::from_utf8(out).unwrap());
+}
+
+#[test]
+fn step_limit_stops_execution() {
+    let (t, msg, out) = run_fizzbuzz(RunOptions {
+        max_steps: Some(5),
+        ..Default::default()
+    });
+    assert_eq!(t, ErrorType::LimitError);
+    assert!(
+        msg.starts_with("Step limit reached after 5 steps, "),
+        "{msg}"
+    );
+    assert!(out.lines().count() < 5);
+}
+
+#[test]
+fn timeout_stops_execution() {
+    let (t, msg, out) = run_fizzbuzz(RunOptions {
+        timeout: Some(Duration::ZERO),
+        ..Default::default()
+    });
+    assert_eq!(t, ErrorType::LimitError);
+    assert!(
+        msg.starts_with("Timeout of 0.000s reached after 0 steps"),
+        "{msg}"
+    );
+    assert_eq!(out, "");
+}
+
+#[test]
+fn limits_from_the_command_line() {
+    Command::cargo_bin("factory")
+        .unwrap()
+        .args(["--no-color", "--max-steps", "5"])
+        .arg(example("fizzbuzz"))
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Step limit reached after 5 steps"));
+
+    Command::cargo_bin("factory")
+        .unwrap()
+        .args(["--no-color", "--timeout", "0"])
+        .arg(example("fizzbuzz"))
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Timeout of 0.000s reached"));
+}
@@ -0,0 +1,166 @@
+use assert_cmd::Command;
+use interpreter::{
+    profile::StationProfile,
+    run,
+    util::{SourcePos, SourceSpan},
+    Profile, RunOptions,
+};
+use predicates::prelude::*;
+use std::{fs, path::PathBuf, time::Duration};
+
+fn for_loop() -> (PathBuf, String) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../examples/for_loop.factory");
+    let src = fs::read_to_string(&path).unwrap();
+    return (path, src);
+}
+
+/// Runs a program with profiling, returns its profile
+fn profile(src: &str) -> Profile {
+    let mut out: Vec<u8> = Vec::new();
+    let report = run(
+        src,
+        &mut RunOptions {
+            output: Box::new(&mut out),
+            profile: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    return report.profile.unwrap();
+}
+
+#[test]
+fn stations_and_functions_are_counted() {
+    let (_, src) = for_loop();
+    let profile = profile(&src);
+
+    let fires: Vec<(&str, String, u64)> = profile
+        .stations
+        .iter()
+        .map(|s| (s.station, s.loc.to_string(), s.fires))
+        .collect();
+    assert_eq!(
+        fires,
+        [
+            ("main", String::from("1:1-7"), 0),
+            ("gate", String::from("1:16-22"), 10),
+            ("assign", String::from("2:8-11"), 1),
+            ("joint", String::from("3:12-14"), 10),
+            ("inc", String::from("3:16-20"), 10),
+            ("gte", String::from("3:22-26"), 10),
+            ("println", String::from("4:1-10"), 10),
+            ("assign", String::from("4:16-20"), 10),
+        ]
+    );
+    assert!(profile.stations.iter().all(|s| s.function == "main"));
+    assert_eq!(profile.functions.len(), 1);
+    assert_eq!(profile.functions[0].invocations, 1);
+}
+
+#[test]
+fn invocations_are_counted() {
+    let src = "\
+[main]═─{1}═─[$f]═─[$f]═─[println]
+
+[$f.0]═─[++]═─[$f.out]
+";
+    let profile = profile(src);
+    let invocations: Vec<(&str, u64)> = profile
+        .functions
+        .iter()
+        .map(|f| (f.name.as_str(), f.invocations))
+        .collect();
+    assert_eq!(invocations, [("main", 1), ("f", 2)]);
+    let inc = profile
+        .stations
+        .iter()
+        .find(|s| s.station == "inc")
+        .unwrap();
+    assert_eq!((inc.function.as_str(), inc.fires), ("f", 2));
+}
+
+#[test]
+fn table_lists_stations_that_triggered() {
+    let (_, src) = for_loop();
+    let mut out: Vec<u8> = Vec::new();
+    profile(&src).write_table(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    let rows: Vec<Vec<&str>> = out
+        .lines()
+        .skip_while(|l| !l.contains("triggers"))
+        .skip(1)
+        .take_while(|l| !l.is_empty())
+        .map(|l| l.split_whitespace().collect())
+        .collect();
+    // most triggered first, without the time column
+    let rows: Vec<(&str, &str, &str)> = rows.iter().map(|r| (r[0], r[2], r[3])).collect();
+    assert_eq!(rows.len(), 7);
+    assert!(rows[..6].iter().all(|(fires, _, _)| *fires == "10"));
+    assert_eq!(rows[6], ("1", "assign", "2:8-11"));
+    assert!(!out.contains("main  "), "{out}");
+    assert!(out.contains("\n          1  main\n"), "{out}");
+}
+
+#[test]
+fn plain_heatmap_writes_trigger_counts() {
+    let (_, src) = for_loop();
+    let mut out: Vec<u8> = Vec::new();
+    profile(&src).write_heatmap(&src, false, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    let expected = [
+        " [main]      ┌─═[gate]─┐",
+        " 0              10",
+        "     ╚──{1}  │   ╔─┘   ║",
+        "        1",
+        "         ╚──[]═─[++]═─[>=]",
+        "            10  10    10",
+        " [println]──╝╚──{10}═──┘",
+        " 10             10",
+        "(trigger counts below each station)",
+    ];
+    assert_eq!(out.lines().collect::<Vec<&str>>(), expected);
+    assert!(!out.contains('\x1b'));
+}
+
+#[test]
+fn wide_counts_keep_apart() {
+    let src = "[main]═─[]═─[]═─[println]";
+    let station = |station, col, len, fires| StationProfile {
+        station,
+        loc: SourceSpan::new(SourcePos::new(0, col), len),
+        function: String::from("main"),
+        fires,
+        time: Duration::ZERO,
+    };
+    let profile = Profile {
+        stations: vec![
+            station("main", 0, 6, 0),
+            station("joint", 8, 2, 123456),
+            station("joint", 12, 2, 123456),
+            station("println", 16, 9, 7),
+        ],
+        functions: Vec::new(),
+    };
+    let mut out: Vec<u8> = Vec::new();
+    profile.write_heatmap(src, false, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.lines().nth(1), Some("0       123456 123456 7"));
+}
+
+#[test]
+fn heatmap_is_printed_without_color() {
+    let (path, _) = for_loop();
+    Command::cargo_bin("factory")
+        .unwrap()
+        .args(["--no-color", "--profile"])
+        .arg(path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("=======Profile======="))
+        .stdout(predicate::str::contains(
+            "         ╚──[]═─[++]═─[>=]\n            10  10    10\n",
+        ))
+        .stdout(predicate::str::contains("\x1b").not());
+}
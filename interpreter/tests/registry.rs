@@ -0,0 +1,98 @@
+use interpreter::{load_with_registry, ErrorType, Pallet, Registry, RunOptions, StationType};
+use std::sync::{
+    atomic::{AtomicI64, Ordering},
+    Arc,
+};
+
+/// Defines a station type that multiplies integers by a shared factor
+fn multiply(
+    id: &'static str,
+    alt_ids: &'static [&'static str],
+    factor: Arc<AtomicI64>,
+) -> StationType {
+    return StationType::host(id, alt_ids, 1, true, move |pallets, _| match &pallets[0] {
+        Pallet::Int(i) => Ok(Some(Pallet::Int(i * factor.load(Ordering::Relaxed)))),
+        p => Err(format!("Expected an integer pallet, received {p}")),
+    });
+}
+
+#[test]
+fn host_stations_run_with_their_captured_state() {
+    let factor = Arc::new(AtomicI64::new(2));
+    let mut registry = Registry::new();
+    registry
+        .register(multiply("double", &[], Arc::clone(&factor)))
+        .unwrap();
+    let program = load_with_registry(
+        "[main]═─{21}═─[double]═─[println]",
+        &registry,
+        &RunOptions::default(),
+    )
+    .unwrap();
+
+    let run = || {
+        let mut out: Vec<u8> = Vec::new();
+        program
+            .run(&mut RunOptions {
+                output: Box::new(&mut out),
+                ..Default::default()
+            })
+            .unwrap();
+        return String::from_utf8(out).unwrap();
+    };
+    assert_eq!(run(), "42\n");
+    // the procedure reads the state on every run, not when it was registered
+    factor.store(3, Ordering::Relaxed);
+    assert_eq!(run(), "63\n");
+}
+
+#[test]
+fn host_station_types_are_dropped_with_their_programs() {
+    let factor = Arc::new(AtomicI64::new(2));
+    let mut registry = Registry::new();
+    registry
+        .register(multiply("double", &[], Arc::clone(&factor)))
+        .unwrap();
+    let program = load_with_registry(
+        "[main]═─{21}═─[double]═─[println]",
+        &registry,
+        &RunOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(Arc::strong_count(&factor), 2);
+
+    // the program keeps the station type alive after the registry is gone
+    drop(registry);
+    assert_eq!(Arc::strong_count(&factor), 2);
+    drop(program);
+    assert_eq!(Arc::strong_count(&factor), 1);
+}
+
+#[test]
+fn host_identifiers_cannot_shadow_builtins() {
+    let factor = Arc::new(AtomicI64::new(2));
+    let mut registry = Registry::new();
+    registry
+        .register(multiply("double", &[], Arc::clone(&factor)))
+        .unwrap();
+
+    for (id, alt_ids, taken) in [
+        ("add", &[][..], "add"),
+        ("plus", &["+"][..], "+"),
+        ("println", &[][..], "println"),
+        ("twice", &["double"][..], "double"),
+    ] {
+        let e = registry
+            .register(multiply(id, alt_ids, Arc::clone(&factor)))
+            .unwrap_err();
+        assert_eq!(e.t, ErrorType::IdentifierError);
+        assert_eq!(
+            e.msg,
+            format!("Station identifier \"{taken}\" is already in use")
+        );
+    }
+    // the rejected station types weren't registered under their other identifiers
+    assert!(registry.find("plus").is_none());
+    assert!(registry.find("twice").is_none());
+    assert_eq!(Arc::strong_count(&factor), 2);
+}
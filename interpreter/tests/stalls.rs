@@ -0,0 +1,75 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use interpreter::{run, ErrorType, RunOptions, StallCheck};
+use predicates::prelude::*;
+
+/// A gate that only ever receives the pallet to let through, never the condition
+const SRC: &str = "[main]═─{1}═─[gate]═─[println]\n";
+
+const STARVED: &str =
+    "Station 'gate' received 1 of the 2 input(s) it needs, but never triggered before 'main' finished";
+
+/// Runs the program with a stall check, returns the warnings of the run or the
+/// type and message of its error
+fn run_with(stall_check: StallCheck) -> Result<Vec<(ErrorType, String)>, (ErrorType, String)> {
+    let mut out: Vec<u8> = Vec::new();
+    let res = run(
+        SRC,
+        &mut RunOptions {
+            output: Box::new(&mut out),
+            stall_check,
+            ..Default::default()
+        },
+    );
+    assert_eq!(out, b"");
+    return match res {
+        Ok(report) => Ok(report.warnings.into_iter().map(|w| (w.t, w.msg)).collect()),
+        Err(errors) => Err((errors[0].t, errors[0].msg.clone())),
+    };
+}
+
+#[test]
+fn stalls_are_ignored_when_off() {
+    assert_eq!(run_with(StallCheck::Off), Ok(Vec::new()));
+}
+
+#[test]
+fn stalls_are_warnings_under_warn() {
+    assert_eq!(
+        run_with(StallCheck::Warn),
+        Ok(vec![(ErrorType::Warning, STARVED.to_owned())])
+    );
+}
+
+#[test]
+fn stalls_are_errors_under_error() {
+    assert_eq!(
+        run_with(StallCheck::Error),
+        Err((ErrorType::RuntimeError, STARVED.to_owned()))
+    );
+}
+
+#[test]
+fn stall_check_from_the_command_line() {
+    let file = assert_fs::NamedTempFile::new("stall.factory").unwrap();
+    file.write_str(SRC).unwrap();
+    let stalls = |mode: &str| {
+        let mut cmd = Command::cargo_bin("factory").unwrap();
+        cmd.args(["--no-color", "--stalls", mode]).arg(file.path());
+        return cmd;
+    };
+
+    stalls("off")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(STARVED).not());
+    stalls("warn")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(STARVED));
+    stalls("error")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Runtime Error @ 1:13-19"))
+        .stdout(predicate::str::contains(STARVED));
+}
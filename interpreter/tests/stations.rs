@@ -0,0 +1,354 @@
+use interpreter::{run, Overflow, RunOptions};
+
+/// Runs a program, returns what it printed or the type and message of the first
+/// error
+fn run_with(src: &str, options: RunOptions) -> Result<String, String> {
+    let mut out: Vec<u8> = Vec::new();
+    let res = run(
+        src,
+        &mut RunOptions {
+            output: Box::new(&mut out),
+            ..options
+        },
+    );
+    return match res {
+        Ok(_) => Ok(String::from_utf8(out).unwrap()),
+        Err(errors) => Err(format!("{}: {}", errors[0].t, errors[0].msg)),
+    };
+}
+
+/// Prints the output of a station given its first input from the north and its
+/// second from the west
+fn binary(a: &str, b: &str, station: &str) -> String {
+    let north = format!("[main]═─{a}═");
+    let west = format!("[main]═─{b}═─");
+    let col = north.chars().count().max(west.chars().count() + 1);
+    return format!(
+        "{north}{}┐\n{}│\n{west}{}[{station}]═─[println]\n",
+        "─".repeat(col - north.chars().count()),
+        " ".repeat(col),
+        "─".repeat(col - 1 - west.chars().count()),
+    );
+}
+
+/// Prints the output of a station given its first two inputs from the north, left
+/// to right, and its third from the west. The station's identifier must be long
+/// enough to reach below the second input
+fn ternary(a: &str, b: &str, c: &str, station: &str) -> String {
+    let first = format!("[main]═─{a}═");
+    let second = format!("[main]═─{b}═");
+    let third = format!("[main]═─{c}═─");
+    let col = first.chars().count().max(third.chars().count() + 1);
+    let col2 = (col + 1).max(second.chars().count());
+    let gap = " ".repeat(col2 - col - 1);
+    return format!(
+        "{second}{}┐\n{first}{}┐{gap}│\n{}│{gap}│\n{third}{}[{station}]═─[println]\n",
+        "─".repeat(col2 - second.chars().count()),
+        "─".repeat(col - first.chars().count()),
+        " ".repeat(col),
+        "─".repeat(col - 1 - third.chars().count()),
+    );
+}
+
+/// Prints a pallet after it went through a station with one input, or just prints
+/// it if the station is empty
+fn unary(a: &str, station: &str) -> Result<String, String> {
+    let src = format!("[main]═─{a}═─[{station}]═─[println]\n");
+    return run_with(&src, RunOptions::default()).map(|out| out.trim_end().to_owned());
+}
+
+#[test]
+fn repeat_is_capped() {
+    let src = binary("{\"ab\"}", "{3}", "repeat");
+    assert_eq!(run_with(&src, RunOptions::default()).unwrap(), "ababab\n");
+
+    let src = binary("{\"ab\"}", "{9999999999999}", "repeat");
+    let err = run_with(&src, RunOptions::default()).unwrap_err();
+    assert!(err.starts_with("Runtime Error"), "{err}");
+    assert!(err.contains("longer than the maximum"), "{err}");
+}
+
+/// Runs a binary station with the default options, returns what it printed
+fn eval(a: &str, b: &str, station: &str) -> String {
+    return run_with(&binary(a, b, station), RunOptions::default())
+        .unwrap()
+        .trim_end()
+        .to_owned();
+}
+
+#[test]
+fn mixed_integers_and_floats_are_promoted() {
+    assert_eq!(eval("{1}", "{2.5}", "+"), "3.5");
+    assert_eq!(eval("{7}", "{2.0}", "/"), "3.5");
+    assert_eq!(eval("{2}", "{0.5}", "pow"), eval("{2.0}", "{0.5}", "pow"));
+    assert_eq!(eval("{1}", "{2.5}", "max"), "2.5");
+    assert_eq!(eval("{7}", "{2}", "/"), "3");
+}
+
+#[test]
+fn mixed_comparisons_are_exact() {
+    assert_eq!(eval("{1}", "{1.0}", "="), "true");
+    assert_eq!(eval("{[1, [2]]}", "{[1.0, [2.0]]}", "="), "true");
+    assert_eq!(eval("{3}", "{3.5}", "<"), "true");
+    assert_eq!(eval("{4}", "{3.5}", "<"), "false");
+    // 2^53 + 1 has no exact float
+    assert_eq!(
+        eval("{9007199254740993}", "{9007199254740992.0}", "="),
+        "false"
+    );
+    assert_eq!(
+        eval("{9007199254740993}", "{9007199254740992.0}", ">"),
+        "true"
+    );
+    assert_eq!(
+        eval("{9007199254740992}", "{9007199254740992.0}", ">="),
+        "true"
+    );
+    assert_eq!(
+        eval("{9223372036854775807}", "{9223372036854775808.0}", "<"),
+        "true"
+    );
+}
+
+#[test]
+fn list_searches_use_equivalence() {
+    assert_eq!(eval("{[1, 2]}", "{2.0}", "contains"), "true");
+    assert_eq!(eval("{[1, 2]}", "{2.0}", "index_of"), "1");
+    assert_eq!(eval("{[1, 2]}", "{3.0}", "index_of"), "-1");
+}
+
+/// Runs a program with an overflow policy
+fn run_overflowing(src: &str, overflow: Overflow) -> Result<String, String> {
+    let res = run_with(
+        src,
+        RunOptions {
+            overflow,
+            ..Default::default()
+        },
+    );
+    return res.map(|out| out.trim_end().to_owned());
+}
+
+#[test]
+fn integer_overflow_follows_the_policy() {
+    let src = binary("{9223372036854775807}", "{1}", "+");
+    let err = run_overflowing(&src, Overflow::Error).unwrap_err();
+    assert!(err.starts_with("Runtime Error"), "{err}");
+    assert!(err.contains("Integer overflow"), "{err}");
+    assert_eq!(
+        run_overflowing(&src, Overflow::Wrapping).unwrap(),
+        "-9223372036854775808"
+    );
+    assert_eq!(
+        run_overflowing(&src, Overflow::Saturating).unwrap(),
+        "9223372036854775807"
+    );
+
+    let src = "[main]═─{9223372036854775807}═─[++]═─[println]\n";
+    assert!(run_overflowing(src, Overflow::Error).is_err());
+    assert_eq!(
+        run_overflowing(src, Overflow::Wrapping).unwrap(),
+        "-9223372036854775808"
+    );
+
+    let src = binary("{2}", "{70}", "pow");
+    assert_eq!(run_overflowing(&src, Overflow::Wrapping).unwrap(), "0");
+    assert_eq!(
+        run_overflowing(&src, Overflow::Saturating).unwrap(),
+        "9223372036854775807"
+    );
+}
+
+#[test]
+fn float_to_integer_overflow_follows_the_policy() {
+    for station in ["floor", "ceil", "round", "to_int"] {
+        let src = format!("[main]═─{{100000000000000000000.0}}═─[{station}]═─[println]\n");
+        let err = run_overflowing(&src, Overflow::Error).unwrap_err();
+        assert!(err.contains("Integer overflow"), "{station}: {err}");
+        // 10^20 - 5 * 2^64
+        assert_eq!(
+            run_overflowing(&src, Overflow::Wrapping).unwrap(),
+            "7766279631452241920",
+            "{station}"
+        );
+        assert_eq!(
+            run_overflowing(&src, Overflow::Saturating).unwrap(),
+            "9223372036854775807",
+            "{station}"
+        );
+    }
+}
+
+#[test]
+fn list_and_map_literals() {
+    assert_eq!(unary("{[1, \"a\", [2]]}", "").unwrap(), "[1, \"a\", [2]]");
+    assert_eq!(
+        unary("{[1, 'c', true, 2.5]}", "").unwrap(),
+        "[1, 'c', true, 2.5]"
+    );
+    assert_eq!(unary("{[]}", "").unwrap(), "[]");
+    assert_eq!(
+        unary("{[\"a\": 1, \"b\": [2]]}", "").unwrap(),
+        "[\"a\": 1, \"b\": [2]]"
+    );
+    assert_eq!(unary("{[:]}", "").unwrap(), "[:]");
+
+    for (literal, msg) in [
+        ("{[1,}", "Unclosed list literal"),
+        ("{[1, , 2]}", "Missing element in list literal"),
+        (
+            "{[\"a\": 1, 2]}",
+            "Literal mixes list elements and map entries",
+        ),
+    ] {
+        let err = unary(literal, "").unwrap_err();
+        assert!(err.contains(msg), "{literal}: {err}");
+    }
+}
+
+#[test]
+fn list_stations() {
+    assert_eq!(eval("{[1]}", "{2}", "push"), "[1, 2]");
+    assert_eq!(unary("{[1, 2, 3]}", "pop").unwrap(), "[1, 2]");
+    assert!(unary("{[]}", "pop").unwrap_err().contains("empty list"));
+    assert_eq!(eval("{[1, 2, 3]}", "{1}", "get"), "2");
+    let err = run_with(&binary("{[1, 2, 3]}", "{3}", "get"), RunOptions::default());
+    assert!(err.unwrap_err().contains("out of bounds"));
+    let src = ternary("{[1, 2, 3]}", "{1}", "{9}", "set");
+    assert_eq!(
+        run_with(&src, RunOptions::default()).unwrap(),
+        "[1, 9, 3]\n"
+    );
+    assert_eq!(unary("{[1, 2, 3]}", "len").unwrap(), "3");
+    assert_eq!(eval("{[1]}", "{[2, 3]}", "concat"), "[1, 2, 3]");
+    let src = ternary("{[1, 2, 3]}", "{1}", "{3}", "slice");
+    assert_eq!(run_with(&src, RunOptions::default()).unwrap(), "[2, 3]\n");
+}
+
+#[test]
+fn map_stations() {
+    let src = ternary("{[:]}", "{\"a\"}", "{1}", "insert");
+    assert_eq!(
+        run_with(&src, RunOptions::default()).unwrap(),
+        "[\"a\": 1]\n"
+    );
+    assert_eq!(eval("{[\"a\": 1]}", "{\"a\"}", "lookup"), "1");
+    assert_eq!(eval("{[\"a\": 1]}", "{\"b\"}", "lookup"), "");
+    assert_eq!(eval("{[\"a\": 1]}", "{\"a\"}", "remove"), "[:]");
+    assert_eq!(eval("{[\"a\": 1]}", "{\"a\"}", "has_key"), "true");
+    assert_eq!(
+        unary("{[\"b\": 2, \"a\": 1]}", "keys").unwrap(),
+        "[\"a\", \"b\"]"
+    );
+    assert_eq!(unary("{[\"b\": 2, \"a\": 1]}", "values").unwrap(), "[1, 2]");
+
+    let err = run_with(&binary("{[:]}", "{[1]}", "has_key"), RunOptions::default());
+    assert!(err.unwrap_err().contains("can't be a map key"));
+}
+
+#[test]
+fn conversion_stations() {
+    assert_eq!(unary("{[1, \"a\"]}", "to_string").unwrap(), "[1, \"a\"]");
+    assert_eq!(unary("{\" 1_000 \"}", "to_int").unwrap(), "1000");
+    assert_eq!(unary("{2.9}", "to_int").unwrap(), "2");
+    assert!(unary("{\"x\"}", "to_int")
+        .unwrap_err()
+        .contains("Failed to parse"));
+    assert_eq!(unary("{\"2.5\"}", "to_float").unwrap(), "2.5");
+    assert_eq!(unary("{\"true\"}", "to_bool").unwrap(), "true");
+    assert_eq!(unary("{0}", "to_bool").unwrap(), "false");
+    assert_eq!(unary("{7}", "to_char").unwrap(), "7");
+    assert!(unary("{12}", "to_char").unwrap_err().contains("[chr]"));
+    assert_eq!(unary("{65}", "chr").unwrap(), "A");
+    assert_eq!(unary("{'A'}", "ord").unwrap(), "65");
+}
+
+#[test]
+fn string_stations() {
+    assert_eq!(eval("{\"héllo\"}", "{1}", "char_at"), "é");
+    let src = ternary("{\"héllo\"}", "{1}", "{3}", "substring");
+    assert_eq!(run_with(&src, RunOptions::default()).unwrap(), "él\n");
+    assert_eq!(eval("{\"a,b\"}", "{','}", "split"), "[\"a\", \"b\"]");
+    assert_eq!(eval("{\"hello\"}", "{\"ll\"}", "contains"), "true");
+    assert_eq!(eval("{\"héllo\"}", "{'l'}", "index_of"), "2");
+    assert_eq!(eval("{\"hello\"}", "{'z'}", "index_of"), "-1");
+    let src = ternary("{\"aba\"}", "{'a'}", "{\"c\"}", "replace");
+    assert_eq!(run_with(&src, RunOptions::default()).unwrap(), "cbc\n");
+    assert_eq!(unary("{\" a \"}", "trim").unwrap(), "a");
+    assert_eq!(unary("{\"aB\"}", "to_upper").unwrap(), "AB");
+    assert_eq!(unary("{\"aB\"}", "to_lower").unwrap(), "ab");
+    assert_eq!(unary("{\"abc\"}", "reverse").unwrap(), "cba");
+    assert_eq!(unary("{[1, 2]}", "reverse").unwrap(), "[2, 1]");
+}
+
+#[test]
+fn math_stations() {
+    assert_eq!(eval("{2}", "{10}", "pow"), "1024");
+    assert_eq!(unary("{4}", "sqrt").unwrap(), "2");
+    assert_eq!(unary("{2.5}", "floor").unwrap(), "2");
+    assert_eq!(unary("{2.5}", "ceil").unwrap(), "3");
+    assert_eq!(unary("{2.5}", "round").unwrap(), "3");
+    assert_eq!(unary("{7}", "round").unwrap(), "7");
+    assert_eq!(
+        unary("{9223372036854775807}", "neg").unwrap(),
+        "-9223372036854775807"
+    );
+    assert_eq!(eval("{3}", "{5}", "min"), "3");
+    assert_eq!(eval("{3}", "{5}", "max"), "5");
+    assert_eq!(unary("{0}", "cos").unwrap(), "1");
+    assert_eq!(unary("{1.0}", "ln").unwrap(), "0");
+    assert_eq!(eval("{100}", "{10}", "log"), "2");
+}
+
+#[test]
+fn math_domain_errors() {
+    // negative numbers come from a neg station, there are no negative literals
+    let cases = [
+        (
+            unary("{4}═─[neg]", "sqrt"),
+            "(Pallet<i:-4>) is outside the domain of sqrt",
+        ),
+        (
+            unary("{0.0}", "ln"),
+            "(Pallet<f:0>) is outside the domain of ln",
+        ),
+        (
+            unary("{4.0}═─[neg]", "ln"),
+            "(Pallet<f:-4>) is outside the domain of ln",
+        ),
+    ];
+    for (res, msg) in cases {
+        assert_eq!(res.unwrap_err(), format!("Runtime Error: {msg}"));
+    }
+
+    let cases = [
+        (
+            "{8}═─[neg]",
+            "{0.5}",
+            "pow",
+            "(Pallet<f:-8>, Pallet<f:0.5>) is outside the domain of pow",
+        ),
+        (
+            "{8}",
+            "{0}",
+            "log",
+            "(Pallet<i:8>, Pallet<i:0>) is outside the domain of log",
+        ),
+        (
+            "{8}",
+            "{1}",
+            "log",
+            "(Pallet<i:8>, Pallet<i:1>) is outside the domain of log",
+        ),
+        (
+            "{0}",
+            "{2}",
+            "log",
+            "(Pallet<i:0>, Pallet<i:2>) is outside the domain of log",
+        ),
+    ];
+    for (a, b, station, msg) in cases {
+        let err = run_with(&binary(a, b, station), RunOptions::default()).unwrap_err();
+        assert_eq!(err, format!("Runtime Error: {msg}"));
+    }
+}
@@ -0,0 +1,59 @@
+use interpreter::{run, RunOptions};
+use serde_json::{json, Value};
+
+/// Runs a program with a trace, returns the parsed trace lines
+fn trace(src: &str) -> Vec<Value> {
+    let mut out: Vec<u8> = Vec::new();
+    let mut trace: Vec<u8> = Vec::new();
+    let res = run(
+        src,
+        &mut RunOptions {
+            output: Box::new(&mut out),
+            trace: Some(Box::new(&mut trace)),
+            ..Default::default()
+        },
+    );
+    assert!(res.is_ok());
+    return String::from_utf8(trace)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+}
+
+#[test]
+fn every_step_is_a_line_of_json() {
+    let steps = trace("[main]═─{1}═─[++]═─[println]\n");
+
+    assert_eq!(steps.len(), 3);
+    for (i, step) in steps.iter().enumerate() {
+        assert_eq!(step["step"], i);
+        assert_eq!(step["fired"].as_array().unwrap().len(), 1);
+    }
+    let fired: Vec<&Value> = steps.iter().map(|s| &s["fired"][0]["station"]).collect();
+    assert_eq!(fired, ["assign", "inc", "println"]);
+
+    let inc = &steps[1]["fired"][0];
+    assert_eq!(inc["function"], "main");
+    assert_eq!(inc["loc"], json!({"line": 1, "col": 13, "len": 4}));
+    assert_eq!(inc["inputs"], json!([{"type": "int", "value": 1}]));
+    assert_eq!(inc["output"], json!({"type": "int", "value": 2}));
+    assert_eq!(steps[2]["fired"][0]["output"], Value::Null);
+}
+
+#[test]
+fn lists_and_maps_are_encoded_recursively() {
+    let steps = trace("[main]═─{[\"a\": [1, 2.5]]}═─[println]\n");
+
+    let map = json!({"type": "map", "value": [[
+        {"type": "string", "value": "a"},
+        {"type": "list", "value": [
+            {"type": "int", "value": 1},
+            {"type": "float", "value": 2.5},
+        ]},
+    ]]});
+    assert_eq!(steps[0]["fired"][0]["output"], map);
+    assert_eq!(steps[1]["fired"][0]["inputs"], json!([map]));
+    assert_eq!(steps[1]["moved"][0]["pallet"], map);
+    assert_eq!(steps[1]["moved"][0]["bay"], json!([2, 0]));
+}